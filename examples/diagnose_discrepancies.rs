@@ -1,7 +1,7 @@
 //! Diagnostic tool to analyze PDF validation discrepancies
 //!
 //! This tool examines PDFs that fail Rust validation but pass Python validation
-//! to understand the specific errors lopdf reports.
+//! to understand the specific errors pdf-rs reports.
 
 use pdf_validator_rs::prelude::*;
 use std::fs::File;
@@ -49,21 +49,21 @@ fn process_file_list(list_path: &PathBuf) {
         }
 
         total += 1;
-        let (is_valid, error_msg) = validate_pdf_detailed(&path);
-
-        if !is_valid {
-            if let Some(msg) = error_msg {
-                // Extract error category
-                let category = extract_error_category(&msg);
-                error_categories.entry(category.clone())
-                    .or_insert_with(Vec::new)
-                    .push(path.to_string_lossy().to_string());
-
-                println!("FILE: {}", path.display());
-                println!("  STATUS: INVALID");
-                println!("  ERROR: {}", msg);
-                println!();
-            }
+        let outcome = validate_pdf_outcome(&path);
+
+        if !outcome.is_valid() {
+            let category = outcome
+                .category
+                .map(|c| c.to_string())
+                .unwrap_or_else(|| "unknown".to_string());
+            error_categories.entry(category)
+                .or_default()
+                .push(path.to_string_lossy().to_string());
+
+            println!("FILE: {}", path.display());
+            println!("  STATUS: {}", outcome.verdict);
+            println!("  ERROR: {}", outcome.message.as_deref().unwrap_or(""));
+            println!();
         }
     }
 
@@ -88,10 +88,16 @@ fn process_single_file(path: &PathBuf) {
     println!("Analyzing: {}", path.display());
     println!("==================================================\n");
 
-    // Test with lopdf detailed
-    let (is_valid, error_msg) = validate_pdf_detailed(path);
-    println!("Lopdf validation: {}", if is_valid { "VALID" } else { "INVALID" });
-    if let Some(msg) = error_msg {
+    // Test with pdf-rs
+    let outcome = validate_pdf_outcome(path);
+    println!("pdf-rs validation: {}", outcome.verdict);
+    if let Some(category) = outcome.category {
+        println!("  Category: {}", category);
+    }
+    if let Some(stage) = outcome.stage {
+        println!("  Stage: {}", stage);
+    }
+    if let Some(ref msg) = outcome.message {
         println!("  Error: {}", msg);
     }
     println!();
@@ -106,22 +112,3 @@ fn process_single_file(path: &PathBuf) {
         println!("File size: {} bytes", metadata.len());
     }
 }
-
-fn extract_error_category(error_msg: &str) -> String {
-    // Extract the main error category from lopdf error messages
-    if error_msg.contains("Xref") {
-        "Xref/Cross-reference table error".to_string()
-    } else if error_msg.contains("EOF") || error_msg.contains("end of file") {
-        "EOF/End of file error".to_string()
-    } else if error_msg.contains("Invalid") || error_msg.contains("invalid") {
-        "Invalid structure/syntax".to_string()
-    } else if error_msg.contains("encrypt") || error_msg.contains("Encrypt") {
-        "Encryption error".to_string()
-    } else if error_msg.contains("object") {
-        "Object reference error".to_string()
-    } else if error_msg.contains("stream") {
-        "Stream error".to_string()
-    } else {
-        error_msg.split(':').next().unwrap_or("Unknown").to_string()
-    }
-}
@@ -0,0 +1,30 @@
+//! Crash-safe file replacement
+
+use serde::Serialize;
+use std::fs::{self, File};
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+
+use crate::core::error::Result;
+
+/// Replace `path` with `value` serialized as JSON
+pub(crate) fn write_json_atomically<T: Serialize>(path: &Path, value: &T) -> Result<()> {
+    write_atomically(path, |writer| Ok(serde_json::to_writer(writer, value)?))
+}
+
+/// Replace `path` with whatever `write` produces
+///
+/// The data is written to a sibling temporary file, flushed to disk and
+/// renamed over `path`, so readers (and a run killed mid-write) see either
+/// the old file or the complete new one.
+pub(crate) fn write_atomically(path: &Path, write: impl FnOnce(&mut BufWriter<File>) -> Result<()>) -> Result<()> {
+    let mut temp = path.as_os_str().to_owned();
+    temp.push(".tmp");
+    let temp = PathBuf::from(temp);
+
+    let mut writer = BufWriter::new(File::create(&temp)?);
+    write(&mut writer)?;
+    writer.into_inner().map_err(|e| e.into_error())?.sync_all()?;
+    fs::rename(&temp, path)?;
+    Ok(())
+}
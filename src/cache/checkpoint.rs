@@ -0,0 +1,585 @@
+//! Resumable progress of a single run
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
+
+use super::atomic_file::write_atomically;
+use crate::core::error::Result;
+use crate::scanner::archive::{split_member_path, MEMBER_SEPARATOR};
+use crate::scanner::file_scanner::ValidationResult;
+
+/// Size and modification time of a file when it was validated
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileStamp {
+    pub size: u64,
+    pub modified: Option<SystemTime>,
+}
+
+impl FileStamp {
+    /// Current stamp of the file at `path`, following symlinks
+    pub fn of(path: &Path) -> Option<Self> {
+        let metadata = fs::metadata(path).ok()?;
+        Some(Self {
+            size: metadata.len(),
+            modified: metadata.modified().ok(),
+        })
+    }
+}
+
+/// Why a checkpoint does not match the current run
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CheckpointMismatch {
+    /// Written by a version that did not record roots and configuration
+    Unverifiable,
+    /// Written for other scan roots
+    Roots {
+        checkpoint: Vec<PathBuf>,
+        current: Vec<PathBuf>,
+    },
+    /// Written with other scan or validation options
+    Config,
+}
+
+impl fmt::Display for CheckpointMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let list = |paths: &[PathBuf]| {
+            paths
+                .iter()
+                .map(|p| p.display().to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        match self {
+            CheckpointMismatch::Unverifiable => {
+                write!(f, "checkpoint does not record its scan roots or options")
+            }
+            CheckpointMismatch::Roots { checkpoint, current } => write!(
+                f,
+                "checkpoint was written for {} but this run scans {}",
+                list(checkpoint),
+                list(current)
+            ),
+            CheckpointMismatch::Config => {
+                write!(f, "checkpoint was written with different scan or validation options")
+            }
+        }
+    }
+}
+
+/// Checkpoint data for resuming validation
+///
+/// On disk a checkpoint is a JSON Lines log: a header line with the roots
+/// and options of the run, then one line per completed file with its stamp
+/// and results. Lines are only ever appended while a run is going, so the
+/// cost of a checkpoint does not grow with the number of files already
+/// done. A line that does not parse, such as the torn last line of a run
+/// killed mid-append, is skipped when loading.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Checkpoint {
+    /// Files (or archives) that have been fully processed
+    pub completed_paths: Vec<PathBuf>,
+    /// Results for the completed files; empty in checkpoints from older versions
+    #[serde(default)]
+    pub results: Vec<ValidationResult>,
+    pub timestamp: SystemTime,
+    /// Files known to the run when the checkpoint was written
+    pub total_files: usize,
+    /// Scan roots (or a hash of the file list) of the run
+    #[serde(default)]
+    pub roots: Vec<PathBuf>,
+    /// Fingerprint of the scan and validation options of the run
+    #[serde(default)]
+    pub config: String,
+    /// Identity of each completed file when it was validated
+    #[serde(default)]
+    pub file_stamps: HashMap<PathBuf, FileStamp>,
+}
+
+/// Version of the checkpoint log format
+const LOG_VERSION: u32 = 2;
+
+/// First line of a checkpoint log
+#[derive(Debug, Serialize, Deserialize)]
+struct LogHeader {
+    /// Missing from the single-document checkpoints of older versions
+    version: u32,
+    timestamp: SystemTime,
+    roots: Vec<PathBuf>,
+    config: String,
+}
+
+/// A completed file as appended to the log
+#[derive(Debug, Serialize)]
+struct LogEntry<'a> {
+    path: &'a Path,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stamp: Option<FileStamp>,
+    results: &'a [ValidationResult],
+    total_files: usize,
+}
+
+/// A completed file as read back from the log
+#[derive(Debug, Deserialize)]
+struct LoggedFile {
+    path: PathBuf,
+    #[serde(default)]
+    stamp: Option<FileStamp>,
+    results: Vec<ValidationResult>,
+    total_files: usize,
+}
+
+impl Default for Checkpoint {
+    fn default() -> Self {
+        Self {
+            completed_paths: Vec::new(),
+            results: Vec::new(),
+            timestamp: SystemTime::now(),
+            total_files: 0,
+            roots: Vec::new(),
+            config: String::new(),
+            file_stamps: HashMap::new(),
+        }
+    }
+}
+
+impl Checkpoint {
+    /// Load checkpoint from file
+    ///
+    /// Reads both checkpoint logs and the single JSON documents written by
+    /// older versions.
+    pub fn load(path: &Path) -> Result<Self> {
+        let mut lines = BufReader::new(File::open(path)?).lines();
+        let first = lines.next().transpose()?.unwrap_or_default();
+        let header: LogHeader = match serde_json::from_str(&first) {
+            Ok(header) => header,
+            Err(_) => return Ok(serde_json::from_str(&first)?),
+        };
+
+        let mut checkpoint = Checkpoint {
+            timestamp: header.timestamp,
+            roots: header.roots,
+            config: header.config,
+            ..Checkpoint::default()
+        };
+        for line in lines {
+            // A torn line loses only its own file, which is validated again
+            let Ok(file) = serde_json::from_str::<LoggedFile>(&line?) else {
+                continue;
+            };
+            if let Some(stamp) = file.stamp {
+                checkpoint.file_stamps.insert(file.path.clone(), stamp);
+            }
+            checkpoint.completed_paths.push(file.path);
+            checkpoint.results.extend(file.results);
+            checkpoint.total_files = file.total_files;
+        }
+        Ok(checkpoint)
+    }
+
+    /// Save checkpoint to file, replacing any previous one atomically
+    pub fn save(&self, path: &Path) -> Result<()> {
+        write_atomically(path, |writer| self.write_log(writer))
+    }
+
+    /// Write the header and one line per completed file
+    fn write_log(&self, writer: &mut impl Write) -> Result<()> {
+        let header = LogHeader {
+            version: LOG_VERSION,
+            timestamp: self.timestamp,
+            roots: self.roots.clone(),
+            config: self.config.clone(),
+        };
+        serde_json::to_writer(&mut *writer, &header)?;
+        writer.write_all(b"\n")?;
+
+        let completed: HashSet<&Path> = self.completed_paths.iter().map(PathBuf::as_path).collect();
+        let mut results: HashMap<&Path, Vec<ValidationResult>> = HashMap::new();
+        for result in &self.results {
+            if let Some(source) = source_of(&result.path, &completed) {
+                results.entry(source).or_default().push(result.clone());
+            }
+        }
+        for path in &self.completed_paths {
+            let entry = LogEntry {
+                path,
+                stamp: self.file_stamps.get(path).copied(),
+                results: results.get(path.as_path()).map_or(&[], Vec::as_slice),
+                total_files: self.total_files,
+            };
+            serde_json::to_writer(&mut *writer, &entry)?;
+            writer.write_all(b"\n")?;
+        }
+        Ok(())
+    }
+
+    /// Check the checkpoint was written for these roots and options
+    pub fn check_compatible(&self, roots: &[PathBuf], config: &str) -> std::result::Result<(), CheckpointMismatch> {
+        if self.roots.is_empty() && self.config.is_empty() {
+            return Err(CheckpointMismatch::Unverifiable);
+        }
+        if self.roots != roots {
+            return Err(CheckpointMismatch::Roots {
+                checkpoint: self.roots.clone(),
+                current: roots.to_vec(),
+            });
+        }
+        if self.config != config {
+            return Err(CheckpointMismatch::Config);
+        }
+        Ok(())
+    }
+
+    /// Forget completed files that changed or vanished since they were validated
+    ///
+    /// Their results are dropped too, so they are validated again. Files
+    /// without a recorded stamp (older checkpoints) are kept. Returns the
+    /// paths that were forgotten.
+    pub fn drop_changed(&mut self) -> Vec<PathBuf> {
+        let changed: HashSet<PathBuf> = self
+            .completed_paths
+            .iter()
+            .filter(|path| {
+                self.file_stamps
+                    .get(*path)
+                    .is_some_and(|stamp| FileStamp::of(path) != Some(*stamp))
+            })
+            .cloned()
+            .collect();
+        if changed.is_empty() {
+            return Vec::new();
+        }
+
+        self.completed_paths.retain(|path| !changed.contains(path));
+        self.results.retain(|result| {
+            let from = split_member_path(&result.path).map(|(archive, _)| archive);
+            !changed.contains(from.as_ref().unwrap_or(&result.path))
+        });
+        self.file_stamps.retain(|path, _| !changed.contains(path));
+        changed.into_iter().collect()
+    }
+}
+
+/// The completed file a result belongs to: the file itself, or the archive
+/// it is a member of
+fn source_of<'a>(result: &Path, completed: &HashSet<&'a Path>) -> Option<&'a Path> {
+    if let Some(path) = completed.get(result) {
+        return Some(path);
+    }
+    let text = result.to_str()?;
+    text.match_indices(MEMBER_SEPARATOR)
+        .find_map(|(i, _)| completed.get(Path::new(&text[..i])))
+        .copied()
+}
+
+/// Appends completed files to the checkpoint log every so often
+///
+/// A batch is appended once `every_files` files have finished or `every`
+/// has elapsed since the last one, whichever comes first, so a run killed
+/// without warning loses at most that much work. Finished files are only
+/// buffered under the lock; the append and fsync happen outside it, so
+/// workers are not held up by the disk. The first write replaces any
+/// existing file with the header and the files of the resumed checkpoint.
+pub struct Checkpointer {
+    path: PathBuf,
+    every_files: usize,
+    every: Duration,
+    /// Files completed by the resumed run
+    resumed: usize,
+    state: Mutex<State>,
+    log: Mutex<Log>,
+}
+
+struct State {
+    /// Log lines not yet written
+    pending: Vec<u8>,
+    unsaved: usize,
+    completed: usize,
+    last_save: Instant,
+}
+
+enum Log {
+    /// Not created yet; holds the checkpoint it starts from
+    Unopened(Checkpoint),
+    /// Open for appending; `len` is where the last complete batch ends
+    Open { file: File, len: u64 },
+}
+
+impl Checkpointer {
+    /// Continue from `base` (an empty checkpoint for a fresh run)
+    pub fn new(path: &Path, base: Checkpoint, every_files: usize, every: Duration) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            every_files: every_files.max(1),
+            every,
+            resumed: base.completed_paths.len(),
+            state: Mutex::new(State {
+                pending: Vec::new(),
+                unsaved: 0,
+                completed: 0,
+                last_save: Instant::now(),
+            }),
+            log: Mutex::new(Log::Unopened(base)),
+        }
+    }
+
+    /// Where checkpoints are written
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Record a finished file and its results, saving if a checkpoint is due
+    ///
+    /// Returns whether a checkpoint was written.
+    pub fn record(&self, path: &Path, results: &[ValidationResult], total_files: usize) -> Result<bool> {
+        let entry = LogEntry {
+            path,
+            stamp: FileStamp::of(path),
+            results,
+            total_files,
+        };
+        let mut line = serde_json::to_vec(&entry)?;
+        line.push(b'\n');
+
+        let batch = {
+            let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+            state.pending.extend_from_slice(&line);
+            state.unsaved += 1;
+            state.completed += 1;
+            if state.unsaved < self.every_files && state.last_save.elapsed() < self.every {
+                return Ok(false);
+            }
+            Self::take_pending(&mut state)
+        };
+        self.write(batch)?;
+        Ok(true)
+    }
+
+    /// Write a checkpoint now
+    pub fn save(&self) -> Result<()> {
+        let batch = Self::take_pending(&mut self.state.lock().unwrap_or_else(|e| e.into_inner()));
+        self.write(batch)
+    }
+
+    /// Number of completed files, including those from the resumed checkpoint
+    pub fn completed(&self) -> usize {
+        let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        self.resumed + state.completed
+    }
+
+    fn take_pending(state: &mut State) -> Vec<u8> {
+        // Reset first so a failing disk is retried on the next interval, not every file
+        state.unsaved = 0;
+        state.last_save = Instant::now();
+        std::mem::take(&mut state.pending)
+    }
+
+    /// Append `batch` to the log, creating it on first use
+    ///
+    /// A batch that cannot be written is put back to be retried with the
+    /// next one. Whatever part of it did reach the file is cut off before
+    /// the retry, so a failed append never leaves a torn line mid-log.
+    fn write(&self, batch: Vec<u8>) -> Result<()> {
+        let mut log = self.log.lock().unwrap_or_else(|e| e.into_inner());
+        let written = match *log {
+            Log::Open { ref mut file, ref mut len } => append(file, len, &batch),
+            Log::Unopened(ref mut base) => {
+                base.timestamp = SystemTime::now();
+                self.create(base, &batch).map(|(file, len)| *log = Log::Open { file, len })
+            }
+        };
+        if written.is_err() {
+            let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+            state.pending.splice(0..0, batch);
+        }
+        written
+    }
+
+    fn create(&self, base: &Checkpoint, batch: &[u8]) -> Result<(File, u64)> {
+        write_atomically(&self.path, |writer| {
+            base.write_log(writer)?;
+            Ok(writer.write_all(batch)?)
+        })?;
+        let file = OpenOptions::new().append(true).open(&self.path)?;
+        let len = file.metadata()?.len();
+        Ok((file, len))
+    }
+}
+
+/// Append `batch` after the first `len` bytes of the log
+fn append(file: &mut File, len: &mut u64, batch: &[u8]) -> Result<()> {
+    file.set_len(*len)?;
+    file.write_all(batch)?;
+    file.sync_data()?;
+    *len += batch.len() as u64;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::outcome::{Strategy, ValidationOutcome};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_checkpointer_saves_every_n_files_with_results() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("run.checkpoint");
+        let checkpointer = Checkpointer::new(&path, Checkpoint::default(), 2, Duration::from_secs(3600));
+
+        let result = |name: &str| ValidationResult::new(PathBuf::from(name), ValidationOutcome::valid(Strategy::PdfRs));
+        assert!(!checkpointer.record(Path::new("a.pdf"), &[result("a.pdf")], 3).unwrap());
+        assert!(!path.exists());
+        assert!(checkpointer.record(Path::new("b.pdf"), &[result("b.pdf")], 3).unwrap());
+
+        let saved = Checkpoint::load(&path).unwrap();
+        assert_eq!(saved.completed_paths.len(), 2);
+        assert_eq!(saved.results.len(), 2);
+        assert_eq!(saved.total_files, 3);
+        assert!(!temp_dir.path().join("run.checkpoint.tmp").exists());
+    }
+
+    #[test]
+    fn test_drop_changed_and_compatibility() {
+        let temp_dir = TempDir::new().unwrap();
+        let same = temp_dir.path().join("same.pdf");
+        let edited = temp_dir.path().join("edited.pdf");
+        std::fs::write(&same, b"%PDF-1.4").unwrap();
+        std::fs::write(&edited, b"%PDF-1.4").unwrap();
+
+        let path = temp_dir.path().join("run.checkpoint");
+        let base = Checkpoint {
+            roots: vec![temp_dir.path().to_path_buf()],
+            config: "abc".to_string(),
+            ..Checkpoint::default()
+        };
+        let checkpointer = Checkpointer::new(&path, base, 1, Duration::from_secs(3600));
+        for file in [&same, &edited] {
+            let result = ValidationResult::new(file.clone(), ValidationOutcome::valid(Strategy::PdfRs));
+            checkpointer.record(file, &[result], 2).unwrap();
+        }
+        std::fs::write(&edited, b"%PDF-1.7 edited").unwrap();
+
+        let mut checkpoint = Checkpoint::load(&path).unwrap();
+        assert_eq!(checkpoint.check_compatible(&[temp_dir.path().to_path_buf()], "abc"), Ok(()));
+        assert_eq!(checkpoint.check_compatible(&[temp_dir.path().to_path_buf()], "xyz"), Err(CheckpointMismatch::Config));
+        assert!(matches!(
+            checkpoint.check_compatible(&[PathBuf::from("/elsewhere")], "abc"),
+            Err(CheckpointMismatch::Roots { .. })
+        ));
+
+        assert_eq!(checkpoint.drop_changed(), vec![edited]);
+        assert_eq!(checkpoint.completed_paths, vec![same.clone()]);
+        assert_eq!(checkpoint.results.len(), 1);
+        assert_eq!(checkpoint.results[0].path, same);
+    }
+
+    #[test]
+    fn test_loads_checkpoint_without_results() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("old.checkpoint");
+        std::fs::write(
+            &path,
+            r#"{"completed_paths":["/a.pdf"],"timestamp":{"secs_since_epoch":0,"nanos_since_epoch":0},"total_files":1}"#,
+        )
+        .unwrap();
+
+        let checkpoint = Checkpoint::load(&path).unwrap();
+        assert_eq!(checkpoint.completed_paths, vec![PathBuf::from("/a.pdf")]);
+        assert!(checkpoint.results.is_empty());
+        assert_eq!(checkpoint.check_compatible(&[], ""), Err(CheckpointMismatch::Unverifiable));
+    }
+
+    #[test]
+    fn test_log_is_appended_and_survives_a_torn_line() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("run.checkpoint");
+        let checkpointer = Checkpointer::new(&path, Checkpoint::default(), 1, Duration::from_secs(3600));
+
+        let result = |name: &str| ValidationResult::new(PathBuf::from(name), ValidationOutcome::valid(Strategy::PdfRs));
+        checkpointer.record(Path::new("a.pdf"), &[result("a.pdf")], 2).unwrap();
+        checkpointer.record(Path::new("b.pdf"), &[result("b.pdf")], 2).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap().lines().count(), 3);
+
+        // A run killed in the middle of an append
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(br#"{"path":"c.pdf","res"#).unwrap();
+
+        let saved = Checkpoint::load(&path).unwrap();
+        assert_eq!(saved.completed_paths, vec![PathBuf::from("a.pdf"), PathBuf::from("b.pdf")]);
+        assert_eq!(saved.results.len(), 2);
+        assert_eq!(saved.total_files, 2);
+    }
+
+    #[test]
+    fn test_torn_line_mid_log_is_skipped_and_cut_before_retry() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("run.checkpoint");
+        let result = |name: &str| ValidationResult::new(PathBuf::from(name), ValidationOutcome::valid(Strategy::PdfRs));
+
+        // A log whose middle line was torn, followed by later complete lines
+        let checkpointer = Checkpointer::new(&path, Checkpoint::default(), 1, Duration::from_secs(3600));
+        checkpointer.record(Path::new("a.pdf"), &[result("a.pdf")], 3).unwrap();
+        drop(checkpointer);
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(b"{\"path\":\"b.pdf\",\"res\n").unwrap();
+        let entry = LogEntry {
+            path: Path::new("c.pdf"),
+            stamp: None,
+            results: &[result("c.pdf")],
+            total_files: 3,
+        };
+        serde_json::to_writer(&mut file, &entry).unwrap();
+        file.write_all(b"\n").unwrap();
+
+        let saved = Checkpoint::load(&path).unwrap();
+        assert_eq!(saved.completed_paths, vec![PathBuf::from("a.pdf"), PathBuf::from("c.pdf")]);
+        assert_eq!(saved.results.len(), 2);
+
+        // The part of a failed append that reached the disk is cut off before the retry
+        std::fs::remove_file(&path).unwrap();
+        let checkpointer = Checkpointer::new(&path, Checkpoint::default(), 1, Duration::from_secs(3600));
+        checkpointer.record(Path::new("a.pdf"), &[result("a.pdf")], 2).unwrap();
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(br#"{"path":"b.pdf","res"#).unwrap();
+        checkpointer.record(Path::new("b.pdf"), &[result("b.pdf")], 2).unwrap();
+
+        let log = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(log.lines().count(), 3);
+        assert!(log.lines().all(|line| serde_json::from_str::<serde_json::Value>(line).is_ok()));
+    }
+
+    #[test]
+    fn test_resumed_checkpoint_keeps_archive_member_results() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("run.checkpoint");
+        let member = |name: &str| {
+            ValidationResult::new(PathBuf::from(format!("/gone/bundle.zip!/{}", name)), ValidationOutcome::valid(Strategy::PdfRs))
+        };
+        let base = Checkpoint {
+            completed_paths: vec![PathBuf::from("/gone/bundle.zip")],
+            results: vec![member("a.pdf"), member("b.pdf")],
+            roots: vec![PathBuf::from("/gone")],
+            config: "abc".to_string(),
+            ..Checkpoint::default()
+        };
+        base.save(&path).unwrap();
+
+        // Resuming writes to the checkpoint it was loaded from
+        let checkpointer = Checkpointer::new(&path, Checkpoint::load(&path).unwrap(), 10, Duration::from_secs(3600));
+        assert_eq!(checkpointer.completed(), 1);
+        let result = ValidationResult::new(PathBuf::from("/gone/c.pdf"), ValidationOutcome::valid(Strategy::PdfRs));
+        checkpointer.record(Path::new("/gone/c.pdf"), &[result], 2).unwrap();
+        checkpointer.save().unwrap();
+
+        let saved = Checkpoint::load(&path).unwrap();
+        assert_eq!(saved.check_compatible(&[PathBuf::from("/gone")], "abc"), Ok(()));
+        assert_eq!(saved.completed_paths.len(), 2);
+        assert_eq!(saved.results.len(), 3);
+    }
+}
@@ -0,0 +1,15 @@
+//! State persisted between runs
+//!
+//! Nightly re-scans of large, mostly static trees spend nearly all their
+//! time re-validating files that have not changed. The result cache
+//! remembers each file's results together with its size and modification
+//! time, so unchanged files can be reported without being opened.
+//! Checkpoints record the progress of a single run so it can be resumed
+//! after an interruption or a crash.
+
+mod atomic_file;
+pub mod checkpoint;
+pub mod result_cache;
+
+pub use checkpoint::{Checkpoint, CheckpointMismatch, Checkpointer, FileStamp};
+pub use result_cache::{cache_fingerprint, CacheEntry, ResultCache};
@@ -0,0 +1,346 @@
+//! Single-file result cache keyed by path, size and modification time
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File};
+use std::io::{BufReader, ErrorKind};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use super::atomic_file::write_json_atomically;
+use crate::core::error::Result;
+use crate::core::options::ValidationOptions;
+use crate::core::outcome::{FailureCategory, Verdict};
+use crate::scanner::duplicate_detector::compute_file_hash;
+use crate::scanner::file_scanner::ValidationResult;
+
+/// Bumped whenever the on-disk layout changes
+const CACHE_FORMAT: u32 = 1;
+
+/// Identify the validator build and configuration results were produced by
+///
+/// `extra` covers settings outside [`ValidationOptions`] that still affect
+/// results, such as content sniffing. A cache written under a different
+/// fingerprint is discarded on open.
+pub fn cache_fingerprint(options: &ValidationOptions, extra: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(env!("CARGO_PKG_VERSION"));
+    hasher.update([0]);
+    hasher.update(serde_json::to_vec(options).unwrap_or_default());
+    hasher.update([0]);
+    hasher.update(extra);
+    format!("{:x}", hasher.finalize())
+}
+
+/// Cached results for one file, valid while its identity is unchanged
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheEntry {
+    pub size: u64,
+    pub modified: Option<SystemTime>,
+    /// Content hash, recorded when the cache verifies hashes
+    pub sha256: Option<String>,
+    /// One result for a PDF, one per member for an archive
+    pub results: Vec<ValidationResult>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CacheFile {
+    format: u32,
+    fingerprint: String,
+    entries: HashMap<PathBuf, CacheEntry>,
+}
+
+/// Results of earlier runs, looked up by file identity
+///
+/// Safe to share between validation threads. Changes are only written by
+/// [`save`](Self::save). The whole cache is held in memory and rewritten on
+/// every save, so its cost grows with the number of files it covers: at
+/// hundreds of thousands of files expect a cache of a few hundred megabytes
+/// and a noticeable pause when a run starts and ends.
+pub struct ResultCache {
+    path: PathBuf,
+    fingerprint: String,
+    verify_hash: bool,
+    entries: Mutex<HashMap<PathBuf, CacheEntry>>,
+    /// Files looked up, stored or kept during this run
+    seen: Mutex<HashSet<PathBuf>>,
+}
+
+impl ResultCache {
+    /// An empty cache that will be saved to `path`
+    pub fn new(path: &Path, fingerprint: String, verify_hash: bool) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            fingerprint,
+            verify_hash,
+            entries: Mutex::new(HashMap::new()),
+            seen: Mutex::new(HashSet::new()),
+        }
+    }
+
+    /// Load the cache at `path`
+    ///
+    /// A missing file, or one written by another validator version or with
+    /// other options, yields an empty cache. With `verify_hash` a hit also
+    /// requires the file's SHA-256 to match, catching edits that preserve
+    /// size and mtime at the cost of reading every file.
+    pub fn open(path: &Path, fingerprint: String, verify_hash: bool) -> Result<Self> {
+        let cache = Self::new(path, fingerprint, verify_hash);
+        let file = match File::open(path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(cache),
+            Err(e) => return Err(e.into()),
+        };
+
+        let cached: CacheFile = serde_json::from_reader(BufReader::new(file))?;
+        if cached.format == CACHE_FORMAT && cached.fingerprint == cache.fingerprint {
+            if let Ok(mut entries) = cache.entries.lock() {
+                *entries = cached.entries;
+            }
+        }
+        Ok(cache)
+    }
+
+    /// Number of cached files
+    pub fn len(&self) -> usize {
+        self.entries.lock().map(|entries| entries.len()).unwrap_or(0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Cached results for `path` if the file is unchanged since they were stored
+    ///
+    /// Results for a file that has changed are forgotten.
+    pub fn get(&self, path: &Path) -> Option<Vec<ValidationResult>> {
+        self.keep(path);
+        let metadata = fs::metadata(path).ok()?;
+        let entry = {
+            let mut entries = self.entries.lock().ok()?;
+            let entry = entries.get(path)?;
+            if entry.size != metadata.len() || entry.modified != metadata.modified().ok() {
+                entries.remove(path);
+                return None;
+            }
+            entry.clone()
+        };
+        if self.verify_hash && entry.sha256 != compute_file_hash(path).ok() {
+            return None;
+        }
+        Some(entry.results)
+    }
+
+    /// Mark `path` as part of this run so [`prune`](Self::prune) keeps it
+    pub fn keep(&self, path: &Path) {
+        if let Ok(mut seen) = self.seen.lock() {
+            seen.insert(path.to_path_buf());
+        }
+    }
+
+    /// Forget every file under `roots` this run did not look up, store or keep
+    ///
+    /// Call after a complete scan of `roots`, so files that were deleted,
+    /// moved or left out of the scan do not accumulate in the cache. Files
+    /// elsewhere belong to other runs sharing the cache and are left alone.
+    pub fn prune(&self, roots: &[PathBuf]) {
+        let (Ok(mut entries), Ok(seen)) = (self.entries.lock(), self.seen.lock()) else {
+            return;
+        };
+        entries.retain(|path, _| seen.contains(path) || !roots.iter().any(|root| path.starts_with(root)));
+    }
+
+    /// Remember the results for `path`
+    ///
+    /// Results that depend on the run rather than the file (timeouts,
+    /// crashes, memory overruns, panics, I/O errors, circuit-breaker skips,
+    /// including files a fallback accepted after one) are not stored.
+    pub fn insert(&self, path: &Path, results: &[ValidationResult]) {
+        self.keep(path);
+        if results.is_empty() || !results.iter().all(is_cacheable) {
+            return;
+        }
+        let Ok(metadata) = fs::metadata(path) else {
+            return;
+        };
+        let sha256 = if self.verify_hash {
+            match compute_file_hash(path) {
+                Ok(hash) => Some(hash),
+                Err(_) => return,
+            }
+        } else {
+            None
+        };
+
+        let entry = CacheEntry {
+            size: metadata.len(),
+            modified: metadata.modified().ok(),
+            sha256,
+            results: results.to_vec(),
+        };
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.insert(path.to_path_buf(), entry);
+        }
+    }
+
+    /// Write the cache back to disk
+    ///
+    /// The file is replaced atomically, so an interrupted save leaves the
+    /// previous cache intact.
+    pub fn save(&self) -> Result<()> {
+        let entries = self.entries.lock().map(|entries| entries.clone()).unwrap_or_default();
+        let cached = CacheFile {
+            format: CACHE_FORMAT,
+            fingerprint: self.fingerprint.clone(),
+            entries,
+        };
+
+        write_json_atomically(&self.path, &cached)
+    }
+}
+
+/// Whether an outcome describes the file rather than the circumstances of the run
+fn is_cacheable(result: &ValidationResult) -> bool {
+    if result.outcome.category.is_some_and(FailureCategory::is_transient) {
+        return false;
+    }
+    match result.outcome.verdict {
+        Verdict::Valid | Verdict::ValidWithWarnings | Verdict::Invalid => true,
+        Verdict::Skipped => result.outcome.category == Some(FailureCategory::Mislabeled),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::outcome::{Strategy, ValidationOutcome, ValidationStage};
+    use tempfile::TempDir;
+
+    fn valid(path: &Path) -> ValidationResult {
+        ValidationResult::new(path.to_path_buf(), ValidationOutcome::valid(Strategy::PdfRs))
+    }
+
+    #[test]
+    fn test_hit_after_reopen_and_miss_after_change() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache_path = temp_dir.path().join("results.cache");
+        let pdf = temp_dir.path().join("a.pdf");
+        fs::write(&pdf, b"%PDF-1.4 one").unwrap();
+
+        let fingerprint = cache_fingerprint(&ValidationOptions::default(), "");
+        let cache = ResultCache::open(&cache_path, fingerprint.clone(), false).unwrap();
+        assert!(cache.get(&pdf).is_none());
+        cache.insert(&pdf, &[valid(&pdf)]);
+        cache.save().unwrap();
+
+        let cache = ResultCache::open(&cache_path, fingerprint.clone(), false).unwrap();
+        assert_eq!(cache.get(&pdf).unwrap().len(), 1);
+
+        fs::write(&pdf, b"%PDF-1.4 changed").unwrap();
+        assert!(cache.get(&pdf).is_none());
+    }
+
+    #[test]
+    fn test_options_change_invalidates_cache() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache_path = temp_dir.path().join("results.cache");
+        let pdf = temp_dir.path().join("a.pdf");
+        fs::write(&pdf, b"%PDF-1.4").unwrap();
+
+        let options = ValidationOptions::default();
+        let cache = ResultCache::open(&cache_path, cache_fingerprint(&options, ""), false).unwrap();
+        cache.insert(&pdf, &[valid(&pdf)]);
+        cache.save().unwrap();
+
+        let strict = ValidationOptions {
+            verify_streams: true,
+            ..options
+        };
+        let cache = ResultCache::open(&cache_path, cache_fingerprint(&strict, ""), false).unwrap();
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_transient_outcomes_are_not_cached() {
+        let temp_dir = TempDir::new().unwrap();
+        let pdf = temp_dir.path().join("a.pdf");
+        fs::write(&pdf, b"%PDF-1.4").unwrap();
+
+        let cache = ResultCache::open(&temp_dir.path().join("c"), String::new(), true).unwrap();
+        let timed_out = ValidationResult::new(
+            pdf.clone(),
+            ValidationOutcome::timed_out(std::time::Duration::from_secs(1)),
+        );
+        cache.insert(&pdf, &[timed_out]);
+        assert!(cache.get(&pdf).is_none());
+
+        cache.insert(&pdf, &[valid(&pdf)]);
+        assert!(cache.get(&pdf).is_some());
+    }
+
+    #[test]
+    fn test_fallback_after_circuit_open_is_not_cached() {
+        let temp_dir = TempDir::new().unwrap();
+        let pdf = temp_dir.path().join("a.pdf");
+        fs::write(&pdf, b"%PDF-1.4").unwrap();
+
+        let cache = ResultCache::new(&temp_dir.path().join("c"), String::new(), false);
+        let rejected = ValidationOutcome::failure(FailureCategory::CircuitOpen, ValidationStage::Parse, "open");
+        let fallback = ValidationOutcome::valid_with_warnings(Strategy::Basic, rejected);
+        cache.insert(&pdf, &[ValidationResult::new(pdf.clone(), fallback)]);
+        assert!(cache.get(&pdf).is_none());
+
+        let over_budget = ValidationOutcome::failure(FailureCategory::MemoryLimit, ValidationStage::Parse, "too big");
+        cache.insert(&pdf, &[ValidationResult::new(pdf.clone(), over_budget)]);
+        assert!(cache.get(&pdf).is_none());
+    }
+
+    #[test]
+    fn test_prune_forgets_files_not_seen_this_run() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache_path = temp_dir.path().join("results.cache");
+        let kept = temp_dir.path().join("kept.pdf");
+        let gone = temp_dir.path().join("gone.pdf");
+        fs::write(&kept, b"%PDF-1.4").unwrap();
+        fs::write(&gone, b"%PDF-1.4").unwrap();
+
+        let cache = ResultCache::new(&cache_path, String::new(), false);
+        cache.insert(&kept, &[valid(&kept)]);
+        cache.insert(&gone, &[valid(&gone)]);
+        cache.save().unwrap();
+
+        let cache = ResultCache::open(&cache_path, String::new(), false).unwrap();
+        assert!(cache.get(&kept).is_some());
+        cache.prune(&[temp_dir.path().to_path_buf()]);
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn test_prune_keeps_files_of_other_roots_sharing_the_cache() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache_path = temp_dir.path().join("results.cache");
+        let (first, second) = (temp_dir.path().join("first"), temp_dir.path().join("second"));
+        fs::create_dir_all(&first).unwrap();
+        fs::create_dir_all(&second).unwrap();
+        let a = first.join("a.pdf");
+        let b = second.join("b.pdf");
+        fs::write(&a, b"%PDF-1.4").unwrap();
+        fs::write(&b, b"%PDF-1.4").unwrap();
+
+        // One complete run per root, both using the same cache
+        for (root, pdf) in [(&first, &a), (&second, &b)] {
+            let cache = ResultCache::open(&cache_path, String::new(), false).unwrap();
+            cache.insert(pdf, &[valid(pdf)]);
+            cache.prune(std::slice::from_ref(root));
+            cache.save().unwrap();
+        }
+
+        let cache = ResultCache::open(&cache_path, String::new(), false).unwrap();
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get(&a).is_some());
+        assert!(cache.get(&b).is_some());
+    }
+}
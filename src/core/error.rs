@@ -0,0 +1,261 @@
+//! Typed errors for the library API
+
+use pdf::error::PdfError;
+use std::io;
+use thiserror::Error;
+
+use super::outcome::{FailureCategory, PageFailure, StreamFailure, ValidationStage};
+
+/// Result type used throughout the library API
+pub type Result<T> = std::result::Result<T, ValidationError>;
+
+/// Errors returned by the library API
+///
+/// Each variant maps to a [`FailureCategory`] so callers can act on the kind
+/// of failure without parsing messages.
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum ValidationError {
+    /// Reading the file (or writing a report) failed
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+
+    /// File does not start with `%PDF-`
+    #[error("Invalid PDF header")]
+    InvalidHeader,
+
+    /// No `%%EOF` marker near the end of the file
+    #[error("Missing %%EOF marker")]
+    MissingEof,
+
+    /// File exceeds the size ceiling
+    #[error("File too large: {size} bytes (limit {limit})")]
+    TooLarge { size: u64, limit: u64 },
+
+    /// File is below the size floor
+    #[error("File too small: {size} bytes (minimum {min})")]
+    TooSmall { size: u64, min: u64 },
+
+    /// Cross-reference data could not be read
+    #[error("pdf-rs xref error: {0}")]
+    Xref(#[source] PdfError),
+
+    /// pdf-rs could not parse the document
+    #[error("pdf-rs parse error: {0}")]
+    Parse(#[source] PdfError),
+
+    /// Document parsed but has no pages
+    #[error("PDF has no pages")]
+    NoPages,
+
+    /// A page could not be loaded
+    #[error("Failed to load page {page}: {source}")]
+    PageLoad {
+        page: u32,
+        #[source]
+        source: PdfError,
+    },
+
+    /// One or more pages failed a deep page-tree walk
+    #[error("{} page(s) failed deep validation", pages.len())]
+    BrokenPages { pages: Vec<PageFailure> },
+
+    /// One or more streams failed length or decoding checks
+    #[error("{} stream(s) failed verification", streams.len())]
+    BrokenStreams { streams: Vec<StreamFailure> },
+
+    /// Validating the file would exceed the memory budget
+    #[error("Memory limit exceeded: needs {needed} bytes (limit {limit})")]
+    MemoryLimit { needed: u64, limit: u64 },
+
+    /// Circuit breaker rejected the attempt
+    #[error("Circuit breaker is OPEN - too many recent failures")]
+    CircuitOpen,
+
+    /// pdfium could not load or render the document
+    #[cfg(feature = "rendering")]
+    #[error("Rendering failed: {0}")]
+    Rendering(#[from] pdfium_render::prelude::PdfiumError),
+
+    /// Directory traversal failed
+    #[error("Directory walk failed: {0}")]
+    Walk(#[from] walkdir::Error),
+
+    /// Scanning a directory tree failed (including bad globs and ignore files)
+    #[error("Directory scan failed: {0}")]
+    Scan(#[from] ignore::Error),
+
+    /// A ZIP archive could not be read
+    #[error("Archive error: {0}")]
+    Archive(#[from] zip::result::ZipError),
+
+    /// Serializing or deserializing report data failed
+    #[error("Serialization failed: {0}")]
+    Serialization(#[from] serde_json::Error),
+}
+
+impl ValidationError {
+    /// Wrap a pdf-rs parse error, separating out xref failures
+    pub fn from_pdf(error: PdfError) -> Self {
+        if is_xref_error(&error) {
+            ValidationError::Xref(error)
+        } else {
+            ValidationError::Parse(error)
+        }
+    }
+
+    /// Failure category for this error
+    pub fn category(&self) -> FailureCategory {
+        match self {
+            ValidationError::InvalidHeader => FailureCategory::BadHeader,
+            ValidationError::MissingEof => FailureCategory::MissingEof,
+            ValidationError::TooLarge { .. } => FailureCategory::TooLarge,
+            ValidationError::TooSmall { .. } => FailureCategory::TooSmall,
+            ValidationError::Xref(_) => FailureCategory::XrefError,
+            ValidationError::Parse(_) => FailureCategory::ParseError,
+            ValidationError::NoPages => FailureCategory::NoPages,
+            ValidationError::PageLoad { .. } | ValidationError::BrokenPages { .. } => {
+                FailureCategory::PageLoadFailure
+            }
+            ValidationError::BrokenStreams { .. } => FailureCategory::StreamError,
+            ValidationError::CircuitOpen => FailureCategory::CircuitOpen,
+            ValidationError::MemoryLimit { .. } => FailureCategory::MemoryLimit,
+            #[cfg(feature = "rendering")]
+            ValidationError::Rendering(_) => FailureCategory::PageLoadFailure,
+            ValidationError::Io(_)
+            | ValidationError::Walk(_)
+            | ValidationError::Scan(_)
+            | ValidationError::Archive(_)
+            | ValidationError::Serialization(_) => FailureCategory::IoError,
+        }
+    }
+
+    /// Validation stage this error is tied to, if any
+    ///
+    /// I/O errors and memory budget overruns can occur at any stage and
+    /// return `None`.
+    pub fn stage(&self) -> Option<ValidationStage> {
+        match self {
+            ValidationError::InvalidHeader
+            | ValidationError::MissingEof
+            | ValidationError::TooLarge { .. }
+            | ValidationError::TooSmall { .. } => Some(ValidationStage::QuickCheck),
+            ValidationError::Xref(_) | ValidationError::Parse(_) | ValidationError::CircuitOpen => {
+                Some(ValidationStage::Parse)
+            }
+            ValidationError::NoPages
+            | ValidationError::PageLoad { .. }
+            | ValidationError::BrokenPages { .. } => Some(ValidationStage::PageTree),
+            ValidationError::BrokenStreams { .. } => Some(ValidationStage::Streams),
+            #[cfg(feature = "rendering")]
+            ValidationError::Rendering(_) => Some(ValidationStage::PageTree),
+            ValidationError::MemoryLimit { .. }
+            | ValidationError::Io(_)
+            | ValidationError::Walk(_)
+            | ValidationError::Scan(_)
+            | ValidationError::Archive(_)
+            | ValidationError::Serialization(_) => None,
+        }
+    }
+
+    /// Pages that failed deep validation, if any
+    pub fn page_failures(&self) -> &[PageFailure] {
+        match self {
+            ValidationError::BrokenPages { pages } => pages,
+            _ => &[],
+        }
+    }
+
+    /// Streams that failed verification, if any
+    pub fn stream_failures(&self) -> &[StreamFailure] {
+        match self {
+            ValidationError::BrokenStreams { streams } => streams,
+            _ => &[],
+        }
+    }
+
+    /// Whether the document itself parsed and the failure is in its contents
+    pub fn is_structural(&self) -> bool {
+        matches!(self.stage(), Some(ValidationStage::PageTree) | Some(ValidationStage::Streams))
+    }
+}
+
+/// Whether pdf-rs failed while reading the cross-reference table or stream
+///
+/// Decided by the error's variant, then by where pdf-rs raised it. Only a
+/// generic error raised outside the xref parser falls back to its text,
+/// since pdf-rs reports many xref problems as plain messages.
+fn is_xref_error(error: &PdfError) -> bool {
+    let mut error = error;
+    loop {
+        match error {
+            PdfError::XRefStreamType { .. } | PdfError::UnspecifiedXRefEntry { .. } => return true,
+            PdfError::Try { file, source, .. } => {
+                let file = std::path::Path::new(file);
+                if file.ends_with("parser/parse_xref.rs") {
+                    return true;
+                }
+                // Besides locating and reading the xref, the backend only
+                // checks the bounds of reads made for any object
+                if file.ends_with("backend.rs") && !matches!(**source, PdfError::ContentReadPastBoundary) {
+                    return true;
+                }
+                error = source;
+            }
+            PdfError::Shared { source } => error = source,
+            PdfError::Other { msg } => return msg.to_lowercase().contains("xref"),
+            _ => return false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_category_and_stage() {
+        let err = ValidationError::MissingEof;
+        assert_eq!(err.category(), FailureCategory::MissingEof);
+        assert_eq!(err.stage(), Some(ValidationStage::QuickCheck));
+
+        let err = ValidationError::from(io::Error::new(io::ErrorKind::NotFound, "gone"));
+        assert_eq!(err.category(), FailureCategory::IoError);
+        assert_eq!(err.stage(), None);
+        assert!(std::error::Error::source(&err).is_some());
+    }
+
+    #[test]
+    fn test_from_pdf_classifies_by_variant() {
+        let xref = ValidationError::from_pdf(PdfError::UnspecifiedXRefEntry { id: 7 });
+        assert_eq!(xref.category(), FailureCategory::XrefError);
+
+        // The message of a wrapped error mentions xref, but the cause is a missing entry
+        let parse = ValidationError::from_pdf(PdfError::MissingEntry {
+            typ: "XRefTable",
+            field: "Size".to_string(),
+        });
+        assert_eq!(parse.category(), FailureCategory::ParseError);
+
+        let generic = ValidationError::from_pdf(PdfError::Other {
+            msg: "xref offsets loop".to_string(),
+        });
+        assert_eq!(generic.category(), FailureCategory::XrefError);
+    }
+
+    #[test]
+    fn test_from_pdf_recognises_xref_reading_failures() {
+        let load = |xref: &[u8]| {
+            let mut data = b"%PDF-1.4\n1 0 obj\n<< /Type /Catalog >>\nendobj\n".to_vec();
+            data.extend_from_slice(xref);
+            data.extend_from_slice(b"trailer\n<< /Size 5 /Root 1 0 R >>\nstartxref\n45\n%%EOF\n");
+            pdf::file::FileOptions::cached().load(data).err().unwrap()
+        };
+
+        // Neither message says which table failed; pdf-rs raised both while reading it
+        let garbage = load(b"hello world\n");
+        assert_eq!(ValidationError::from_pdf(garbage).category(), FailureCategory::XrefError);
+        let truncated = load(b"xref\n0 5\n0000000000 65535 f \n");
+        assert_eq!(ValidationError::from_pdf(truncated).category(), FailureCategory::XrefError);
+    }
+}
@@ -0,0 +1,41 @@
+//! Locating the `%PDF-` header
+//!
+//! Discovery, the quick check and the basic scan all decide whether a file
+//! starts like a PDF with [`find_pdf_header`], so a file found by content
+//! is never rejected for where its header sits.
+
+/// How far into a file the `%PDF-` header may appear
+///
+/// Readers tolerate leading junk (mail headers, BOMs, download-manager
+/// prefixes) as long as the header shows up within the first kilobyte.
+pub const SNIFF_WINDOW: usize = 1024;
+
+/// Offset of the `%PDF-` header within the sniff window, if present
+pub fn find_pdf_header(data: &[u8]) -> Option<usize> {
+    let window = &data[..data.len().min(SNIFF_WINDOW)];
+    window.windows(5).position(|w| w == b"%PDF-")
+}
+
+/// Version declared by the `%PDF-x.y` header in `data`, if any
+pub fn header_version(data: &[u8]) -> Option<String> {
+    let start = find_pdf_header(data)? + 5;
+    let version: String = data[start..]
+        .iter()
+        .take_while(|b| b.is_ascii_digit() || **b == b'.')
+        .map(|&b| b as char)
+        .collect();
+    (!version.is_empty()).then_some(version)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_header_version_after_leading_junk() {
+        assert_eq!(header_version(b"%PDF-1.7\n"), Some("1.7".to_string()));
+        assert_eq!(header_version(b"\xEF\xBB\xBF%PDF-2.0 x"), Some("2.0".to_string()));
+        assert_eq!(header_version(b"%PDF-"), None);
+        assert_eq!(header_version(b"<html>"), None);
+    }
+}
@@ -0,0 +1,203 @@
+//! Per-validation memory budgets
+//!
+//! [`TrackingAllocator`] counts the bytes each thread allocates, so a
+//! validation can be compared against its budget with a [`MemoryBudget`].
+//! In-process accounting is soft: allocations are never refused, because a
+//! failed allocation aborts the whole process. For a hard ceiling, run
+//! isolated workers, which cap their address space with `RLIMIT_AS` (see
+//! [`limit_address_space`]).
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::io;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Exit code of a worker whose allocation failed under its memory limit
+pub const MEMORY_LIMIT_EXIT_CODE: i32 = 86;
+
+static INSTALLED: AtomicBool = AtomicBool::new(false);
+static EXIT_ON_FAILURE: AtomicBool = AtomicBool::new(false);
+
+/// Bytes allocated by one thread
+struct Usage {
+    current: Cell<u64>,
+    peak: Cell<u64>,
+}
+
+thread_local! {
+    static USAGE: Usage = const {
+        Usage {
+            current: Cell::new(0),
+            peak: Cell::new(0),
+        }
+    };
+}
+
+/// Global allocator that keeps per-thread allocation counts
+///
+/// Install it in the binary to make [`MemoryBudget`] effective:
+///
+/// ```ignore
+/// #[global_allocator]
+/// static ALLOCATOR: TrackingAllocator = TrackingAllocator;
+/// ```
+///
+/// Memory freed on a different thread than it was allocated on is
+/// credited to the freeing thread, so counts are approximate.
+pub struct TrackingAllocator;
+
+unsafe impl GlobalAlloc for TrackingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        record_alloc(ptr, layout.size());
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        record_alloc(ptr, layout.size());
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        record_free(layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            record_free(layout.size());
+        }
+        record_alloc(new_ptr, new_size);
+        new_ptr
+    }
+}
+
+fn record_alloc(ptr: *mut u8, size: usize) {
+    if ptr.is_null() {
+        allocation_failed();
+        return;
+    }
+    if !INSTALLED.load(Ordering::Relaxed) {
+        INSTALLED.store(true, Ordering::Relaxed);
+    }
+    // Fails only while the thread is being torn down
+    let _ = USAGE.try_with(|usage| {
+        let current = usage.current.get() + size as u64;
+        usage.current.set(current);
+        if current > usage.peak.get() {
+            usage.peak.set(current);
+        }
+    });
+}
+
+fn record_free(size: usize) {
+    let _ = USAGE.try_with(|usage| {
+        usage.current.set(usage.current.get().saturating_sub(size as u64));
+    });
+}
+
+/// Leave with a recognisable exit code instead of aborting
+///
+/// Only enabled in workers by [`limit_address_space`]. `_exit` does not
+/// allocate or run destructors, so it is safe to call from the allocator.
+fn allocation_failed() {
+    #[cfg(unix)]
+    if EXIT_ON_FAILURE.load(Ordering::Relaxed) {
+        unsafe { libc::_exit(MEMORY_LIMIT_EXIT_CODE) }
+    }
+}
+
+/// Whether [`TrackingAllocator`] is the global allocator
+pub fn is_installed() -> bool {
+    INSTALLED.load(Ordering::Relaxed)
+}
+
+/// Allocations on the current thread measured against a limit
+pub struct MemoryBudget {
+    limit: u64,
+    baseline: u64,
+}
+
+impl MemoryBudget {
+    /// Start measuring the current thread's allocations against `limit` bytes
+    pub fn start(limit: u64) -> Self {
+        let baseline = USAGE.with(|usage| {
+            usage.peak.set(usage.current.get());
+            usage.current.get()
+        });
+        Self { limit, baseline }
+    }
+
+    pub fn limit(&self) -> u64 {
+        self.limit
+    }
+
+    /// Highest number of bytes held since the budget started
+    pub fn peak(&self) -> u64 {
+        USAGE.with(|usage| usage.peak.get().saturating_sub(self.baseline))
+    }
+
+    /// Whether the peak went over the limit
+    ///
+    /// Always `false` unless [`TrackingAllocator`] is installed.
+    pub fn exceeded(&self) -> bool {
+        is_installed() && self.peak() > self.limit
+    }
+}
+
+/// Cap this process's address space at its current size plus `limit` bytes
+///
+/// Meant for worker processes: once the cap is hit, allocations fail and the
+/// process exits with [`MEMORY_LIMIT_EXIT_CODE`] (when [`TrackingAllocator`]
+/// is installed) instead of growing until the OOM killer steps in. A no-op
+/// on platforms without `setrlimit`.
+pub fn limit_address_space(limit: u64) -> io::Result<()> {
+    #[cfg(unix)]
+    {
+        let cap = current_address_space().unwrap_or(0).saturating_add(limit);
+        let rlimit = libc::rlimit {
+            rlim_cur: cap as libc::rlim_t,
+            rlim_max: cap as libc::rlim_t,
+        };
+        if unsafe { libc::setrlimit(libc::RLIMIT_AS, &rlimit) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        EXIT_ON_FAILURE.store(true, Ordering::Relaxed);
+    }
+    #[cfg(not(unix))]
+    let _ = limit;
+    Ok(())
+}
+
+/// Size of this process's address space, where the platform exposes it
+#[cfg(unix)]
+fn current_address_space() -> Option<u64> {
+    #[cfg(target_os = "linux")]
+    {
+        let statm = std::fs::read_to_string("/proc/self/statm").ok()?;
+        let pages: u64 = statm.split_whitespace().next()?.parse().ok()?;
+        let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) };
+        if page_size <= 0 {
+            return None;
+        }
+        Some(pages * page_size as u64)
+    }
+    #[cfg(not(target_os = "linux"))]
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_budget_is_inert_without_tracking_allocator() {
+        let budget = MemoryBudget::start(1);
+        let buffer = vec![0u8; 1024];
+        assert_eq!(buffer.len(), 1024);
+        assert_eq!(budget.limit(), 1);
+        assert!(!budget.exceeded());
+    }
+}
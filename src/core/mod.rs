@@ -2,3 +2,15 @@
 
 pub mod validator;
 pub mod circuit_breaker;
+pub mod error;
+pub mod header;
+pub mod memory;
+pub mod options;
+pub mod outcome;
+pub mod streams;
+
+pub use error::ValidationError;
+pub use memory::{MemoryBudget, TrackingAllocator};
+pub use options::{Strictness, ValidationLevel, ValidationOptions};
+pub use outcome::{FailureCategory, PageFailure, Strategy, StreamFailure, ValidationOutcome, ValidationStage, Verdict};
+pub use validator::{catch_panic, Validator, ValidatorBuilder};
@@ -0,0 +1,70 @@
+//! Validation options
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use super::outcome::Strategy;
+
+/// How strictly the strategy chain is applied
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Strictness {
+    /// A failed quick check, missing pages or an unreadable page is final
+    Strict,
+    /// Every failure falls through to the next strategy in the chain
+    Lenient,
+}
+
+/// How much of the document pdf-rs is asked to resolve
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ValidationLevel {
+    /// Parse the document and load the first page
+    Standard,
+    /// Resolve every page with its resources, contents and media box
+    Deep,
+}
+
+/// Limits and behaviour of a [`Validator`](super::validator::Validator)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ValidationOptions {
+    /// Files larger than this are rejected by the quick check (bytes)
+    pub max_file_size: u64,
+    /// Files smaller than this are rejected by the quick check (bytes)
+    pub min_file_size: u64,
+    /// How many trailing bytes are searched for `%%EOF`
+    pub eof_search_window: u64,
+    pub strictness: Strictness,
+    pub level: ValidationLevel,
+    /// Decode every stream and check its `/Length` after a successful parse
+    pub verify_streams: bool,
+    /// Per-file time budget; files exceeding it are reported as timed out
+    pub timeout: Option<Duration>,
+    /// Memory budget for validating one file (bytes); a hard cap only in
+    /// isolated workers, checked after the fact in process
+    pub memory_limit: Option<u64>,
+    /// Strategies tried in order; the first is the primary strategy
+    pub strategies: Vec<Strategy>,
+    /// Consecutive pdf-rs failures before the circuit breaker opens
+    pub circuit_breaker_threshold: usize,
+    /// How long the circuit breaker stays open
+    pub circuit_breaker_cooldown: Duration,
+}
+
+impl Default for ValidationOptions {
+    fn default() -> Self {
+        Self {
+            max_file_size: 500_000_000, // 500MB
+            min_file_size: 100,
+            eof_search_window: 1024,
+            strictness: Strictness::Strict,
+            level: ValidationLevel::Standard,
+            verify_streams: false,
+            timeout: None,
+            memory_limit: None,
+            strategies: vec![Strategy::PdfRs, Strategy::Basic],
+            circuit_breaker_threshold: 10,
+            circuit_breaker_cooldown: Duration::from_secs(60),
+        }
+    }
+}
@@ -0,0 +1,495 @@
+//! Structured validation outcomes
+//!
+//! Every validation entry point reports a [`ValidationOutcome`] describing not
+//! only whether a file passed, but why it failed, at which stage, and which
+//! strategy finally accepted it.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::time::Duration;
+
+/// Overall verdict for a single file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Verdict {
+    /// Passed the primary validation strategy
+    Valid,
+    /// Accepted, but only by a fallback strategy or with recoverable issues
+    ValidWithWarnings,
+    /// The file is not a usable PDF
+    Invalid,
+    /// Validation was not attempted (e.g. circuit breaker open)
+    Skipped,
+    /// Validation could not complete (e.g. I/O error)
+    Errored,
+    /// Validation exceeded the per-file time budget
+    TimedOut,
+    /// The isolated worker validating this file crashed
+    Crashed,
+    /// Validation needed more memory than its budget allows
+    ResourceLimitExceeded,
+    /// A validation strategy panicked on this file
+    Panicked,
+}
+
+impl Verdict {
+    /// Whether this verdict counts as a valid PDF
+    pub fn is_valid(self) -> bool {
+        matches!(self, Verdict::Valid | Verdict::ValidWithWarnings)
+    }
+
+    /// Stable machine-readable name
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Verdict::Valid => "valid",
+            Verdict::ValidWithWarnings => "valid_with_warnings",
+            Verdict::Invalid => "invalid",
+            Verdict::Skipped => "skipped",
+            Verdict::Errored => "errored",
+            Verdict::TimedOut => "timed_out",
+            Verdict::Crashed => "crashed",
+            Verdict::ResourceLimitExceeded => "resource_limit_exceeded",
+            Verdict::Panicked => "panicked",
+        }
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Machine-readable reason a file failed validation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FailureCategory {
+    /// File does not start with `%PDF-`
+    BadHeader,
+    /// No `%%EOF` marker near the end of the file
+    MissingEof,
+    /// File exceeds the configured size ceiling
+    TooLarge,
+    /// File is below the configured size floor
+    TooSmall,
+    /// Cross-reference table or stream could not be read
+    XrefError,
+    /// Any other pdf-rs parse failure
+    ParseError,
+    /// Document parsed but contains no pages
+    NoPages,
+    /// A page could not be loaded
+    PageLoadFailure,
+    /// A stream is truncated, has a wrong `/Length` or fails to decode
+    StreamError,
+    /// Circuit breaker rejected the attempt
+    CircuitOpen,
+    /// The file could not be read
+    IoError,
+    /// Validation exceeded the per-file time budget
+    Timeout,
+    /// The worker process died while validating the file
+    WorkerCrash,
+    /// Validation exceeded its memory budget
+    MemoryLimit,
+    /// A validation strategy panicked
+    Panic,
+    /// Named like a PDF but the content is something else
+    Mislabeled,
+    /// A symlink whose target does not exist
+    DanglingLink,
+}
+
+impl FailureCategory {
+    /// Stable machine-readable name
+    pub fn as_str(self) -> &'static str {
+        match self {
+            FailureCategory::BadHeader => "bad_header",
+            FailureCategory::MissingEof => "missing_eof",
+            FailureCategory::TooLarge => "too_large",
+            FailureCategory::TooSmall => "too_small",
+            FailureCategory::XrefError => "xref_error",
+            FailureCategory::ParseError => "parse_error",
+            FailureCategory::NoPages => "no_pages",
+            FailureCategory::PageLoadFailure => "page_load_failure",
+            FailureCategory::StreamError => "stream_error",
+            FailureCategory::CircuitOpen => "circuit_open",
+            FailureCategory::IoError => "io_error",
+            FailureCategory::Timeout => "timeout",
+            FailureCategory::WorkerCrash => "worker_crash",
+            FailureCategory::MemoryLimit => "memory_limit",
+            FailureCategory::Panic => "panic",
+            FailureCategory::Mislabeled => "mislabeled",
+            FailureCategory::DanglingLink => "dangling_link",
+        }
+    }
+
+    /// Whether the category proves the file itself is damaged
+    ///
+    /// Size limits, I/O errors, timeouts, crashes and other failures of the
+    /// validation run say nothing definite about the file.
+    pub fn is_corruption(self) -> bool {
+        matches!(
+            self,
+            FailureCategory::BadHeader
+                | FailureCategory::MissingEof
+                | FailureCategory::XrefError
+                | FailureCategory::ParseError
+                | FailureCategory::NoPages
+                | FailureCategory::PageLoadFailure
+                | FailureCategory::StreamError
+        )
+    }
+
+    /// Whether the failure comes from the circumstances of the run
+    ///
+    /// Circuit-breaker rejections, I/O errors, timeouts, memory overruns,
+    /// worker crashes and panics may not happen again on another run.
+    pub fn is_transient(self) -> bool {
+        matches!(
+            self,
+            FailureCategory::CircuitOpen
+                | FailureCategory::IoError
+                | FailureCategory::Timeout
+                | FailureCategory::WorkerCrash
+                | FailureCategory::MemoryLimit
+                | FailureCategory::Panic
+        )
+    }
+
+    /// One-line human-readable description
+    pub fn description(self) -> &'static str {
+        match self {
+            FailureCategory::BadHeader => "File does not start with a %PDF- header",
+            FailureCategory::MissingEof => "No %%EOF marker near the end of the file",
+            FailureCategory::TooLarge => "File exceeds the configured size limit",
+            FailureCategory::TooSmall => "File is below the configured minimum size",
+            FailureCategory::XrefError => "Cross-reference table or stream could not be read",
+            FailureCategory::ParseError => "Document could not be parsed",
+            FailureCategory::NoPages => "Document contains no pages",
+            FailureCategory::PageLoadFailure => "A page could not be loaded",
+            FailureCategory::StreamError => "A stream is truncated, has a wrong /Length or fails to decode",
+            FailureCategory::CircuitOpen => "Validation skipped because the circuit breaker was open",
+            FailureCategory::IoError => "File could not be read",
+            FailureCategory::Timeout => "Validation exceeded the per-file time limit",
+            FailureCategory::WorkerCrash => "Worker process crashed while validating the file",
+            FailureCategory::MemoryLimit => "Validation exceeded the per-file memory limit",
+            FailureCategory::Panic => "A validation strategy panicked",
+            FailureCategory::Mislabeled => "Named like a PDF but the content is something else",
+            FailureCategory::DanglingLink => "Symlink whose target does not exist",
+        }
+    }
+}
+
+impl fmt::Display for FailureCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Validation stage at which a failure was detected
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ValidationStage {
+    /// Magic bytes, size limits and EOF marker
+    QuickCheck,
+    /// Full document parse with pdf-rs
+    Parse,
+    /// Page tree access after a successful parse
+    PageTree,
+    /// Stream length and filter verification
+    Streams,
+    /// Header/EOF scan of the whole file
+    Basic,
+}
+
+impl ValidationStage {
+    /// Stable machine-readable name
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ValidationStage::QuickCheck => "quick_check",
+            ValidationStage::Parse => "parse",
+            ValidationStage::PageTree => "page_tree",
+            ValidationStage::Streams => "streams",
+            ValidationStage::Basic => "basic",
+        }
+    }
+}
+
+impl fmt::Display for ValidationStage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Validation strategy that accepted a file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Strategy {
+    /// Full parse with pdf-rs
+    PdfRs,
+    /// Header and `%%EOF` scan only
+    Basic,
+}
+
+impl Strategy {
+    /// Stable machine-readable name
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Strategy::PdfRs => "pdf_rs",
+            Strategy::Basic => "basic",
+        }
+    }
+}
+
+impl fmt::Display for Strategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A page that failed deep validation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PageFailure {
+    /// Zero-based page index
+    pub page: u32,
+    pub message: String,
+}
+
+impl fmt::Display for PageFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "page {}: {}", self.page, self.message)
+    }
+}
+
+/// A stream object that failed verification
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StreamFailure {
+    pub object: u32,
+    pub generation: u16,
+    pub message: String,
+}
+
+impl fmt::Display for StreamFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "object {} {}: {}", self.object, self.generation, self.message)
+    }
+}
+
+/// Detailed result of validating one file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ValidationOutcome {
+    pub verdict: Verdict,
+    /// Why the file failed (or, for warnings, why the primary strategy failed)
+    pub category: Option<FailureCategory>,
+    /// Stage at which the failure was detected
+    pub stage: Option<ValidationStage>,
+    /// Underlying error message
+    pub message: Option<String>,
+    /// Strategy that finally accepted the file
+    pub strategy: Option<Strategy>,
+    /// Pages that failed a deep page-tree walk
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub failed_pages: Vec<PageFailure>,
+    /// Streams that failed length or decoding checks
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub failed_streams: Vec<StreamFailure>,
+    /// Page count, when a full parse got far enough to read it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pages: Option<u32>,
+    /// Version from the `%PDF-x.y` header, when the file starts with one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pdf_version: Option<String>,
+}
+
+impl ValidationOutcome {
+    /// File passed the given strategy without issues
+    pub fn valid(strategy: Strategy) -> Self {
+        Self {
+            verdict: Verdict::Valid,
+            category: None,
+            stage: None,
+            message: None,
+            strategy: Some(strategy),
+            failed_pages: Vec::new(),
+            failed_streams: Vec::new(),
+            pages: None,
+            pdf_version: None,
+        }
+    }
+
+    /// File was accepted by `strategy` after an earlier failure
+    pub fn valid_with_warnings(strategy: Strategy, earlier: ValidationOutcome) -> Self {
+        Self {
+            verdict: Verdict::ValidWithWarnings,
+            category: earlier.category,
+            stage: earlier.stage,
+            message: earlier.message,
+            strategy: Some(strategy),
+            failed_pages: earlier.failed_pages,
+            failed_streams: earlier.failed_streams,
+            pages: None,
+            pdf_version: None,
+        }
+    }
+
+    /// File failed at `stage`
+    ///
+    /// The verdict is derived from the category: circuit-breaker rejections,
+    /// mislabeled files and dangling links are `Skipped`, I/O problems are `Errored`, memory budget overruns are
+    /// `ResourceLimitExceeded`, everything else is `Invalid`.
+    pub fn failure(
+        category: FailureCategory,
+        stage: ValidationStage,
+        message: impl Into<String>,
+    ) -> Self {
+        let verdict = match category {
+            FailureCategory::CircuitOpen | FailureCategory::Mislabeled | FailureCategory::DanglingLink => {
+                Verdict::Skipped
+            }
+            FailureCategory::IoError => Verdict::Errored,
+            FailureCategory::MemoryLimit => Verdict::ResourceLimitExceeded,
+            _ => Verdict::Invalid,
+        };
+        Self {
+            verdict,
+            category: Some(category),
+            stage: Some(stage),
+            message: Some(message.into()),
+            strategy: None,
+            failed_pages: Vec::new(),
+            failed_streams: Vec::new(),
+            pages: None,
+            pdf_version: None,
+        }
+    }
+
+    /// Validation did not finish within `limit`
+    pub fn timed_out(limit: Duration) -> Self {
+        Self {
+            verdict: Verdict::TimedOut,
+            category: Some(FailureCategory::Timeout),
+            stage: None,
+            message: Some(format!("Validation exceeded {:?} time limit", limit)),
+            strategy: None,
+            failed_pages: Vec::new(),
+            failed_streams: Vec::new(),
+            pages: None,
+            pdf_version: None,
+        }
+    }
+
+    /// The worker process validating the file died
+    pub fn crashed(message: impl Into<String>) -> Self {
+        Self {
+            verdict: Verdict::Crashed,
+            category: Some(FailureCategory::WorkerCrash),
+            stage: None,
+            message: Some(message.into()),
+            strategy: None,
+            failed_pages: Vec::new(),
+            failed_streams: Vec::new(),
+            pages: None,
+            pdf_version: None,
+        }
+    }
+
+    /// Validation used more than `limit` bytes of memory
+    pub fn memory_limit_exceeded(limit: u64) -> Self {
+        Self {
+            verdict: Verdict::ResourceLimitExceeded,
+            category: Some(FailureCategory::MemoryLimit),
+            stage: None,
+            message: Some(format!("Validation exceeded {} byte memory limit", limit)),
+            strategy: None,
+            failed_pages: Vec::new(),
+            failed_streams: Vec::new(),
+            pages: None,
+            pdf_version: None,
+        }
+    }
+
+    /// A strategy panicked while validating the file
+    pub fn panicked(message: impl Into<String>) -> Self {
+        Self {
+            verdict: Verdict::Panicked,
+            category: Some(FailureCategory::Panic),
+            stage: None,
+            message: Some(message.into()),
+            strategy: None,
+            failed_pages: Vec::new(),
+            failed_streams: Vec::new(),
+            pages: None,
+            pdf_version: None,
+        }
+    }
+
+    /// Whether the file counts as a valid PDF
+    pub fn is_valid(&self) -> bool {
+        self.verdict.is_valid()
+    }
+
+    /// Whether the file was found definitely corrupt, not merely unvalidated
+    pub fn is_corrupt(&self) -> bool {
+        self.verdict == Verdict::Invalid && self.category.is_some_and(FailureCategory::is_corruption)
+    }
+}
+
+impl fmt::Display for ValidationOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.verdict)?;
+        if let Some(category) = self.category {
+            write!(f, " [{}]", category)?;
+        }
+        if let Some(stage) = self.stage {
+            write!(f, " at {}", stage)?;
+        }
+        if let Some(ref message) = self.message {
+            write!(f, ": {}", message)?;
+        }
+        if let Some(strategy) = self.strategy {
+            write!(f, " (accepted by {})", strategy)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_failure_verdict_from_category() {
+        let o = ValidationOutcome::failure(FailureCategory::MissingEof, ValidationStage::QuickCheck, "x");
+        assert_eq!(o.verdict, Verdict::Invalid);
+
+        let o = ValidationOutcome::failure(FailureCategory::CircuitOpen, ValidationStage::Parse, "x");
+        assert_eq!(o.verdict, Verdict::Skipped);
+
+        let o = ValidationOutcome::failure(FailureCategory::IoError, ValidationStage::QuickCheck, "x");
+        assert_eq!(o.verdict, Verdict::Errored);
+        assert!(!o.is_valid());
+        assert!(!o.is_corrupt());
+    }
+
+    #[test]
+    fn test_only_definite_corruption_is_corrupt() {
+        let o = ValidationOutcome::failure(FailureCategory::StreamError, ValidationStage::Streams, "x");
+        assert!(o.is_corrupt());
+
+        let o = ValidationOutcome::failure(FailureCategory::TooLarge, ValidationStage::QuickCheck, "x");
+        assert!(!o.is_corrupt());
+        assert!(!ValidationOutcome::timed_out(std::time::Duration::from_secs(1)).is_corrupt());
+        assert!(!ValidationOutcome::panicked("x").is_corrupt());
+    }
+
+    #[test]
+    fn test_valid_with_warnings_keeps_failure_detail() {
+        let earlier = ValidationOutcome::failure(FailureCategory::XrefError, ValidationStage::Parse, "bad xref");
+        let o = ValidationOutcome::valid_with_warnings(Strategy::Basic, earlier);
+
+        assert!(o.is_valid());
+        assert_eq!(o.category, Some(FailureCategory::XrefError));
+        assert_eq!(o.strategy, Some(Strategy::Basic));
+    }
+}
@@ -0,0 +1,408 @@
+//! Stream decoding verification
+//!
+//! Resolves every in-use object of the cross-reference table of a document
+//! already opened with pdf-rs. pdf-rs checks each stream's resolved
+//! `/Length` against the `endstream` keyword while parsing it; the stream
+//! data is then decoded through the filter chain pdf-rs built from
+//! `/Filter` and `/DecodeParms`. This catches truncated downloads and
+//! corrupt Flate data that still leave an object graph pdf-rs can parse.
+
+use std::io::{self, Write};
+
+use pdf::backend::Backend;
+use pdf::enc::StreamFilter;
+use pdf::error::PdfError;
+use pdf::file::CachedFile;
+use pdf::object::{NoResolve, NoUpdate, Object, ObjectWrite, PlainRef, Resolve, StreamInfo};
+use pdf::parser::{parse_with_lexer, Lexer, ParseFlags};
+use pdf::primitive::{PdfStream, Primitive};
+use pdf::xref::XRef;
+
+use super::outcome::StreamFailure;
+
+/// Verify every stream in a parsed document
+///
+/// `data` is the document pdf-rs parsed `pdf_file` from. Returns one entry
+/// per stream that is truncated, has a wrong `/Length`, or fails to decode.
+pub fn verify_streams<B: Backend>(pdf_file: &CachedFile<B>, data: &[u8]) -> Vec<StreamFailure> {
+    verify_streams_within(pdf_file, data, u64::MAX).unwrap_or_default()
+}
+
+/// [`verify_streams`] with a cap on the size of any decoded stream
+///
+/// Output is counted while it is decoded, so a filter that would produce
+/// more than `limit` bytes is stopped there, never held in memory, and the
+/// number of bytes it had produced by then is returned as the error.
+pub fn verify_streams_within<B: Backend>(
+    pdf_file: &CachedFile<B>,
+    data: &[u8],
+    limit: u64,
+) -> Result<Vec<StreamFailure>, u64> {
+    let resolver = pdf_file.resolver();
+    // pdf-rs keeps its own table private, so read it again for the generations
+    let Ok(start) = data.locate_start_offset() else {
+        return Ok(Vec::new());
+    };
+    let Ok((refs, _)) = data.read_xref_table_and_trailer(start, &resolver) else {
+        return Ok(Vec::new());
+    };
+    let mut failures = Vec::new();
+
+    for id in refs.iter() {
+        let (gen, pos) = match refs.get(id as u64) {
+            Ok(XRef::Raw { pos, gen_nr }) => (gen_nr, Some(pos)),
+            // Objects compressed into an object stream are never streams
+            Ok(XRef::Stream { .. }) => (0, None),
+            _ => continue,
+        };
+        let object = PlainRef { id: id as u64, gen };
+        let problem = match resolver.resolve(object) {
+            Ok(Primitive::Stream(stream)) => match verify_stream(&stream, &resolver, limit) {
+                Ok(()) => continue,
+                Err(Problem::Invalid(message)) => (reference(&stream).unwrap_or(object), message),
+                Err(Problem::OverBudget(size)) => return Err(size),
+            },
+            Ok(_) => continue,
+            Err(PdfError::FreeObject { .. }) | Err(PdfError::NullRef { .. }) => continue,
+            // pdf-rs could not parse the object. For a stream that means a
+            // `/Length` that does not end at `endstream` or a truncated file;
+            // other objects are the parser's and page walk's business.
+            Err(e) => match pos {
+                Some(pos) if is_stream_object(data, start + pos) => (object, format!("cannot be read: {}", e)),
+                _ => continue,
+            },
+        };
+
+        let (object, message) = problem;
+        failures.push(StreamFailure {
+            object: object.id as u32,
+            generation: object.gen as u16,
+            message,
+        });
+    }
+
+    Ok(failures)
+}
+
+/// Whether the indirect object at `pos` is a dictionary followed by `stream`
+fn is_stream_object(data: &[u8], pos: usize) -> bool {
+    let Some(object) = data.get(pos..) else {
+        return false;
+    };
+    let mut lexer = Lexer::with_offset(object, pos);
+    let header = lexer.next().and_then(|_| lexer.next()).and_then(|_| lexer.next_expect("obj"));
+    if header.is_err() {
+        return false;
+    }
+    // Without a parse context pdf-rs refuses to read a stream body, after
+    // having checked that one follows the dictionary
+    matches!(
+        parse_with_lexer(&mut lexer, &NoResolve, ParseFlags::ANY),
+        Err(PdfError::PrimitiveNotAllowed { allowed: ParseFlags::STREAM, .. })
+    )
+}
+
+/// Why a stream did not verify
+enum Problem {
+    Invalid(String),
+    /// Decoded data outgrew the size cap
+    OverBudget(u64),
+}
+
+impl From<String> for Problem {
+    fn from(message: String) -> Self {
+        Problem::Invalid(message)
+    }
+}
+
+/// Object number and generation a stream was parsed from
+fn reference(stream: &PdfStream) -> Option<PlainRef> {
+    match stream.to_primitive(&mut NoUpdate) {
+        Ok(Primitive::Reference(reference)) => Some(reference),
+        _ => None,
+    }
+}
+
+fn verify_stream(stream: &PdfStream, resolver: &impl Resolve, limit: u64) -> Result<(), Problem> {
+    let info = StreamInfo::<Primitive>::from_primitive(Primitive::Dictionary(stream.info.clone()), resolver)
+        .map_err(|e| format!("stream dictionary: {}", e))?;
+    let raw = stream.raw_data(resolver).map_err(|e| format!("stream data: {}", e))?;
+
+    let mut buffer = raw.to_vec();
+    for filter in &info.filters {
+        match filter {
+            // JPEG data: checked for start and end markers only
+            StreamFilter::DCTDecode(_) => {
+                return check_jpeg(&buffer).map_err(|e| Problem::Invalid(format!("{}: {}", filter_name(filter), e)))
+            }
+            StreamFilter::ASCIIHexDecode
+            | StreamFilter::ASCII85Decode
+            | StreamFilter::LZWDecode(_)
+            | StreamFilter::FlateDecode(_)
+            | StreamFilter::RunLengthDecode => {
+                // Filters that can expand their input are measured before they are decoded
+                let expanded = expanded_size(&buffer, filter, limit).map_err(|e| format!("{}: {}", filter_name(filter), e))?;
+                if let Some(size) = expanded.filter(|&size| size > limit) {
+                    return Err(Problem::OverBudget(size));
+                }
+                buffer = pdf::enc::decode(&buffer, filter).map_err(|e| format!("{}: {}", filter_name(filter), e))?;
+                if buffer.len() as u64 > limit {
+                    return Err(Problem::OverBudget(buffer.len() as u64));
+                }
+            }
+            // Filters we cannot verify end the chain
+            _ => return Ok(()),
+        }
+    }
+
+    Ok(())
+}
+
+/// Size of `data` decoded through an expanding `filter`, counted without
+/// keeping the output
+///
+/// Counting stops once it passes `limit`. Returns `None` for the hex and
+/// ASCII85 filters, which only ever shrink their input; predictors never
+/// grow inflated data either.
+fn expanded_size(data: &[u8], filter: &StreamFilter, limit: u64) -> Result<Option<u64>, String> {
+    match filter {
+        StreamFilter::FlateDecode(_) => {
+            let mut counter = Counter::new(limit);
+            let zlib = io::copy(&mut flate2::read::ZlibDecoder::new(data), &mut counter);
+            if zlib.is_ok() || counter.over() {
+                return Ok(Some(counter.written));
+            }
+            // pdf-rs also accepts raw deflate data without a zlib header
+            let mut counter = Counter::new(limit);
+            match io::copy(&mut flate2::read::DeflateDecoder::new(data), &mut counter) {
+                Ok(_) => Ok(Some(counter.written)),
+                Err(_) if counter.over() => Ok(Some(counter.written)),
+                Err(e) => Err(format!("can't inflate: {}", e)),
+            }
+        }
+        StreamFilter::LZWDecode(params) => {
+            use weezl::{decode::Decoder, BitOrder};
+            let mut decoder = if params.early_change != 0 {
+                Decoder::with_tiff_size_switch(BitOrder::Msb, 9)
+            } else {
+                Decoder::new(BitOrder::Msb, 9)
+            };
+            let mut counter = Counter::new(limit);
+            match decoder.into_stream(&mut counter).decode_all(data).status {
+                Ok(()) => Ok(Some(counter.written)),
+                Err(_) if counter.over() => Ok(Some(counter.written)),
+                Err(e) => Err(e.to_string()),
+            }
+        }
+        StreamFilter::RunLengthDecode => Ok(Some(run_length_size(data))),
+        _ => Ok(None),
+    }
+}
+
+/// Output size of RunLength data, read from its length bytes
+fn run_length_size(data: &[u8]) -> u64 {
+    let mut size = 0;
+    let mut at = 0;
+    while let Some(&length) = data.get(at) {
+        match length {
+            0..=127 => {
+                size += length as u64 + 1;
+                at += length as usize + 2;
+            }
+            // End of data
+            128 => break,
+            _ => {
+                size += 257 - length as u64;
+                at += 2;
+            }
+        }
+    }
+    size
+}
+
+/// A sink counting the bytes written to it, failing once past its limit
+struct Counter {
+    written: u64,
+    limit: u64,
+}
+
+impl Counter {
+    fn new(limit: u64) -> Self {
+        Self { written: 0, limit }
+    }
+
+    fn over(&self) -> bool {
+        self.written > self.limit
+    }
+}
+
+impl Write for Counter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.written += buf.len() as u64;
+        if self.over() {
+            return Err(io::Error::other("decoded stream over the size limit"));
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn filter_name(filter: &StreamFilter) -> &'static str {
+    match filter {
+        StreamFilter::ASCIIHexDecode => "ASCIIHexDecode",
+        StreamFilter::ASCII85Decode => "ASCII85Decode",
+        StreamFilter::LZWDecode(_) => "LZWDecode",
+        StreamFilter::FlateDecode(_) => "FlateDecode",
+        StreamFilter::JPXDecode => "JPXDecode",
+        StreamFilter::DCTDecode(_) => "DCTDecode",
+        StreamFilter::CCITTFaxDecode(_) => "CCITTFaxDecode",
+        StreamFilter::JBIG2Decode(_) => "JBIG2Decode",
+        StreamFilter::Crypt => "Crypt",
+        StreamFilter::RunLengthDecode => "RunLengthDecode",
+    }
+}
+
+/// JPEG sanity check: SOI marker at the start and EOI marker at the end
+fn check_jpeg(data: &[u8]) -> Result<(), String> {
+    if !data.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        return Err("missing JPEG start-of-image marker".to_string());
+    }
+    let end = data.len() - data.iter().rev().take_while(|&&b| is_whitespace(b)).count();
+    if !data[..end].ends_with(&[0xFF, 0xD9]) {
+        return Err("missing JPEG end-of-image marker (truncated image)".to_string());
+    }
+    Ok(())
+}
+
+fn is_whitespace(b: u8) -> bool {
+    matches!(b, b'\0' | b'\t' | b'\n' | b'\x0c' | b'\r' | b' ')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pdf::file::FileOptions;
+
+    /// Assemble objects `1 0 obj`, `2 0 obj`, ... into a document with an
+    /// xref table and load it with pdf-rs, returning it with its bytes
+    fn load(objects: &[&[u8]]) -> (CachedFile<Vec<u8>>, Vec<u8>) {
+        let objects: Vec<(u16, &[u8])> = objects.iter().map(|body| (0, *body)).collect();
+        load_generations(&objects)
+    }
+
+    /// [`load`] with a generation number for each object
+    fn load_generations(objects: &[(u16, &[u8])]) -> (CachedFile<Vec<u8>>, Vec<u8>) {
+        let mut data = b"%PDF-1.4\n".to_vec();
+        let mut offsets = Vec::new();
+        for (index, (gen, body)) in objects.iter().enumerate() {
+            offsets.push((data.len(), gen));
+            data.extend(format!("{} {} obj\n", index + 1, gen).into_bytes());
+            data.extend_from_slice(body);
+            data.extend_from_slice(b"\nendobj\n");
+        }
+        let xref = data.len();
+        data.extend(format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).into_bytes());
+        for (offset, gen) in offsets {
+            data.extend(format!("{:010} {:05} n \n", offset, gen).into_bytes());
+        }
+        data.extend(
+            format!("trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n", objects.len() + 1, xref)
+                .into_bytes(),
+        );
+        (FileOptions::cached().load(data.clone()).unwrap(), data)
+    }
+
+    const CATALOG: &[u8] = b"<< /Type /Catalog /Pages 2 0 R >>";
+    const PAGES: &[u8] = b"<< /Type /Pages /Kids [] /Count 0 >>";
+
+    #[test]
+    fn test_detects_length_mismatch() {
+        let (pdf_file, data) = load(&[
+            CATALOG,
+            PAGES,
+            b"<< /Length 10 >>\nstream\nq Q\nendstream",
+            b"<< /Length 3 >>\nstream\nq Q\nendstream",
+        ]);
+
+        let failures = verify_streams(&pdf_file, &data);
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].object, 3);
+        assert_eq!(failures[0].generation, 0);
+    }
+
+    #[test]
+    fn test_resolves_indirect_length() {
+        let (pdf_file, data) = load(&[CATALOG, PAGES, b"<< /Length 4 0 R >>\nstream\nq Q\nendstream", b"3"]);
+
+        assert!(verify_streams(&pdf_file, &data).is_empty());
+    }
+
+    #[test]
+    fn test_ignores_stream_keywords_inside_strings() {
+        let (pdf_file, data) = load(&[CATALOG, PAGES, b"<< /Title (1 0 obj << /Length 99 >> stream) >>"]);
+
+        assert!(verify_streams(&pdf_file, &data).is_empty());
+    }
+
+    #[test]
+    fn test_detects_corrupt_hex_and_truncated_jpeg() {
+        let (pdf_file, data) = load(&[
+            CATALOG,
+            PAGES,
+            b"<< /Length 6 /Filter /ASCIIHexDecode >>\nstream\nzz00>>\nendstream",
+            b"<< /Length 4 /Filter [/DCTDecode] >>\nstream\n\xFF\xD8\xFF\xE0\nendstream",
+        ]);
+
+        let failures = verify_streams(&pdf_file, &data);
+        let objects: Vec<u32> = failures.iter().map(|f| f.object).collect();
+        assert_eq!(objects, vec![3, 4]);
+    }
+
+    #[test]
+    fn test_stops_on_oversized_decoded_stream() {
+        let (pdf_file, data) = load(&[CATALOG, PAGES, b"<< /Length 9 /Filter /ASCIIHexDecode >>\nstream\n41424344>\nendstream"]);
+
+        assert_eq!(verify_streams_within(&pdf_file, &data, 3), Err(4));
+        assert!(verify_streams_within(&pdf_file, &data, 4).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_reports_generation_from_xref_table() {
+        let (pdf_file, data) = load_generations(&[
+            (0, CATALOG),
+            (0, PAGES),
+            (3, b"<< /Length 10 >>\nstream\nq Q\nendstream"),
+        ]);
+
+        let failures = verify_streams(&pdf_file, &data);
+        assert_eq!(failures.len(), 1);
+        assert_eq!((failures[0].object, failures[0].generation), (3, 3));
+    }
+
+    #[test]
+    fn test_ignores_unreadable_objects_that_are_not_streams() {
+        let (pdf_file, data) = load(&[CATALOG, PAGES, b"<< /Broken [1 2 >>"]);
+
+        assert!(verify_streams(&pdf_file, &data).is_empty());
+    }
+
+    #[test]
+    fn test_stops_inflating_at_the_limit() {
+        use flate2::write::ZlibEncoder;
+
+        let mut encoder = ZlibEncoder::new(Vec::new(), flate2::Compression::best());
+        encoder.write_all(&vec![0u8; 16 << 20]).unwrap();
+        let bomb = encoder.finish().unwrap();
+        let mut stream = format!("<< /Length {} /Filter /FlateDecode >>\nstream\n", bomb.len()).into_bytes();
+        stream.extend_from_slice(&bomb);
+        stream.extend_from_slice(b"\nendstream");
+        let (pdf_file, data) = load(&[CATALOG, PAGES, &stream]);
+
+        let limit = 1 << 20;
+        let needed = verify_streams_within(&pdf_file, &data, limit).unwrap_err();
+        assert!(needed > limit && needed < 2 * limit, "{}", needed);
+    }
+}
@@ -1,188 +1,813 @@
 //! PDF validation logic
 
-use anyhow::Result;
+use std::any::Any;
 use std::fs::File;
-use std::io::{Read, Seek, SeekFrom};
-use std::path::Path;
+use std::io::{Cursor, Read, Seek, SeekFrom};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::atomic::{AtomicU8, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
 
 #[cfg(feature = "rendering")]
 use pdfium_render::prelude::*;
 
 use super::circuit_breaker::CircuitBreaker;
-use std::time::Duration;
+use super::error::{Result, ValidationError};
+use super::header::{self, find_pdf_header, SNIFF_WINDOW};
+use super::memory::MemoryBudget;
+use super::options::{Strictness, ValidationLevel, ValidationOptions};
+use super::outcome::{PageFailure, Strategy, ValidationOutcome, ValidationStage, Verdict};
+use super::streams::verify_streams_within;
 
-// Circuit breaker for tracking repeated failures
+// Validator backing the free functions below
 lazy_static::lazy_static! {
-    static ref CIRCUIT_BREAKER: CircuitBreaker = CircuitBreaker::new(10, Duration::from_secs(60));
-}
-
-/// Quick pre-validation before attempting full parse
-/// Checks PDF magic bytes, file size, and EOF marker
-fn quick_validate(path: &Path) -> Result<()> {
-    let mut file = File::open(path)?;
-    
-    // 1. Check PDF magic bytes (%PDF-)
-    let mut header = [0u8; 8];
-    file.read_exact(&mut header)?;
-    if &header[0..5] != b"%PDF-" {
-        anyhow::bail!("Invalid PDF header");
-    }
-    
-    // 2. Check file size
-    let metadata = file.metadata()?;
-    let file_size = metadata.len();
-    
-    if file_size > 500_000_000 { // 500MB
-        anyhow::bail!("File too large: {} bytes", file_size);
-    }
-    
-    if file_size < 100 {
-        anyhow::bail!("File too small: {} bytes", file_size);
-    }
-    
-    // 3. Check for EOF marker (%%EOF) in last 1KB
-    let tail_size = std::cmp::min(1024, file_size);
-    file.seek(SeekFrom::End(-(tail_size as i64)))?;
-    let mut tail = vec![0u8; tail_size as usize];
-    file.read_exact(&mut tail)?;
-    
-    if !tail.windows(5).any(|w| w == b"%%EOF") {
-        anyhow::bail!("Missing %%EOF marker");
-    }
-    
-    Ok(())
+    static ref DEFAULT_VALIDATOR: Validator = Validator::default();
 }
 
-/// Validate a PDF file
+/// Convert an error raised during `stage` into a failure outcome
+fn failure_outcome(stage: ValidationStage, error: &ValidationError) -> ValidationOutcome {
+    let mut outcome =
+        ValidationOutcome::failure(error.category(), error.stage().unwrap_or(stage), error.to_string());
+    outcome.failed_pages = error.page_failures().to_vec();
+    outcome.failed_streams = error.stream_failures().to_vec();
+    outcome
+}
+
+/// Version declared by a `%PDF-x.y` header at the start of the document
+fn header_version(source: Source<'_>) -> Option<String> {
+    match source {
+        Source::Path(path) => read_header_version(&mut File::open(path).ok()?),
+        Source::Bytes(mut data) => read_header_version(&mut data),
+    }
+}
+
+/// Version declared by a `%PDF-x.y` header read from the current position
+fn read_header_version(reader: &mut impl Read) -> Option<String> {
+    let mut prefix = Vec::with_capacity(SNIFF_WINDOW + 16);
+    reader.take(SNIFF_WINDOW as u64 + 16).read_to_end(&mut prefix).ok()?;
+    header::header_version(&prefix)
+}
+
+/// Run `f`, turning a panic into its message
 ///
-/// # Arguments
-/// * `path` - Path to the PDF file
-/// * `verbose` - Whether to print verbose error messages
+/// The validator uses this around every strategy so a parser bug on one
+/// file cannot unwind through a whole batch.
+pub fn catch_panic<T>(f: impl FnOnce() -> T) -> std::result::Result<T, String> {
+    panic::catch_unwind(AssertUnwindSafe(f)).map_err(|payload| panic_message(payload.as_ref()))
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "panic with a non-string payload".to_string()
+    }
+}
+
+/// Most validation threads left running past their timeout at any one time
+pub const MAX_ABANDONED_THREADS: usize = 32;
+
+/// Validation threads still running after their caller gave up on them
+static ABANDONED_THREADS: AtomicUsize = AtomicUsize::new(0);
+
+/// States of a watchdog-supervised validation thread
+const RUNNING: u8 = 0;
+const FINISHED: u8 = 1;
+const ABANDONED: u8 = 2;
+
+/// Where the document being validated comes from
+#[derive(Clone, Copy)]
+enum Source<'a> {
+    Path(&'a Path),
+    Bytes(&'a [u8]),
+}
+
+impl Source<'_> {
+    fn into_owned(self) -> OwnedSource {
+        match self {
+            Source::Path(path) => OwnedSource::Path(path.to_path_buf()),
+            Source::Bytes(data) => OwnedSource::Bytes(data.to_vec()),
+        }
+    }
+
+    fn len(self) -> Result<u64> {
+        match self {
+            Source::Path(path) => Ok(std::fs::metadata(path)?.len()),
+            Source::Bytes(data) => Ok(data.len() as u64),
+        }
+    }
+}
+
+/// A [`Source`] that can be moved to a watchdog thread
+enum OwnedSource {
+    Path(PathBuf),
+    Bytes(Vec<u8>),
+}
+
+impl OwnedSource {
+    fn as_source(&self) -> Source<'_> {
+        match self {
+            OwnedSource::Path(path) => Source::Path(path),
+            OwnedSource::Bytes(data) => Source::Bytes(data),
+        }
+    }
+}
+
+/// The bytes of a document, shared between pdf-rs and the checks that read
+/// them again after it parsed them
+#[derive(Clone)]
+struct Document(Arc<Vec<u8>>);
+
+impl Document {
+    fn read(source: Source<'_>) -> std::result::Result<Self, pdf::error::PdfError> {
+        let data = match source {
+            Source::Path(path) => std::fs::read(path)?,
+            Source::Bytes(data) => data.to_vec(),
+        };
+        Ok(Self(Arc::new(data)))
+    }
+}
+
+impl std::ops::Deref for Document {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+type ParsedFile = pdf::file::CachedFile<Document>;
+
+/// Configurable PDF validator
 ///
-/// # Returns
-/// `true` if the PDF is valid, `false` otherwise
-pub fn validate_pdf(path: &Path, verbose: bool) -> bool {
-    // Quick pre-validation (no semaphore needed)
-    if let Err(e) = quick_validate(path) {
-        if verbose {
-            eprintln!("Quick validation failed for {:?}: {}", path, e);
-        }
-        return false;
-    }
-    
-    // Try using pdf-rs for robust validation (thread-safe, pure Rust)
-    match validate_pdf_with_pdf_rs(path) {
-        Ok(valid) => {
-            if verbose && !valid {
-                eprintln!("Invalid (pdf-rs): {:?}", path);
+/// Owns its limits, strictness, strategy chain and circuit breaker, so
+/// differently configured validators can run side by side in one process.
+/// Clones share the circuit breaker.
+#[derive(Clone)]
+pub struct Validator {
+    options: ValidationOptions,
+    circuit_breaker: Arc<CircuitBreaker>,
+    parse_hook: Option<ParseHook>,
+}
+
+/// Callback run on each file just before pdf-rs parses it
+///
+/// A test seam: a hook that panics stands in for a parser bug triggered by
+/// one specific file, and is contained exactly like a panic inside pdf-rs.
+#[doc(hidden)]
+pub type ParseHook = Arc<dyn Fn(&Path) + Send + Sync>;
+
+impl Default for Validator {
+    fn default() -> Self {
+        Self::new(ValidationOptions::default())
+    }
+}
+
+impl Validator {
+    /// Create a validator with the given options
+    pub fn new(mut options: ValidationOptions) -> Self {
+        if options.strategies.is_empty() {
+            options.strategies = ValidationOptions::default().strategies;
+        }
+        let circuit_breaker = Arc::new(CircuitBreaker::new(
+            options.circuit_breaker_threshold,
+            options.circuit_breaker_cooldown,
+        ));
+        Self {
+            options,
+            circuit_breaker,
+            parse_hook: None,
+        }
+    }
+
+    /// Run `hook` on each file just before pdf-rs parses it
+    #[doc(hidden)]
+    pub fn with_parse_hook(mut self, hook: ParseHook) -> Self {
+        self.parse_hook = Some(hook);
+        self
+    }
+
+    /// Start building a validator from the default options
+    pub fn builder() -> ValidatorBuilder {
+        ValidatorBuilder::new()
+    }
+
+    pub fn options(&self) -> &ValidationOptions {
+        &self.options
+    }
+
+    pub fn circuit_breaker(&self) -> &CircuitBreaker {
+        &self.circuit_breaker
+    }
+
+    /// Validate a file using the configured strictness
+    pub fn validate(&self, path: &Path) -> ValidationOutcome {
+        self.validate_with_strictness(path, self.options.strictness)
+    }
+
+    /// Validate a file, overriding the configured strictness
+    ///
+    /// Runs the quick pre-checks and then each strategy in the chain. The
+    /// first strategy to accept the file decides the outcome; if it is not
+    /// the primary strategy, or the quick check failed in lenient mode, the
+    /// outcome is `ValidWithWarnings` and carries the first failure. A
+    /// strategy that panics ends the chain with a `Panicked` outcome and
+    /// counts as a failure for the circuit breaker.
+    pub fn validate_with_strictness(&self, path: &Path, strictness: Strictness) -> ValidationOutcome {
+        self.validate_source(Source::Path(path), strictness)
+    }
+
+    /// Validate a document held in memory
+    ///
+    /// Runs the same checks as [`validate`](Self::validate). With a timeout
+    /// configured the data is copied so the watchdog thread can own it.
+    pub fn validate_bytes(&self, data: &[u8]) -> ValidationOutcome {
+        self.validate_source(Source::Bytes(data), self.options.strictness)
+    }
+
+    /// Validate a document read from the start of `reader`
+    ///
+    /// In strict mode the quick checks run against the reader itself, so a
+    /// non-PDF or oversized document is rejected without reading it all.
+    /// Otherwise the document is read into memory and validated as bytes.
+    pub fn validate_reader<R: Read + Seek>(&self, mut reader: R) -> ValidationOutcome {
+        if self.options.strictness == Strictness::Strict {
+            if let Err(e) = self.quick_check(&mut reader) {
+                let mut outcome = failure_outcome(ValidationStage::QuickCheck, &e);
+                if reader.seek(SeekFrom::Start(0)).is_ok() {
+                    outcome.pdf_version = read_header_version(&mut reader);
+                }
+                return outcome;
             }
-            valid
         }
-        Err(e) => {
-            if verbose {
-                eprintln!("Error validating {:?}: {}", path, e);
+        match self.read_document(&mut reader) {
+            Ok(data) => self.validate_bytes(&data),
+            Err(e) => failure_outcome(ValidationStage::QuickCheck, &e),
+        }
+    }
+
+    /// Read a whole document, refusing ones larger than the memory budget
+    fn read_document<R: Read + Seek>(&self, reader: &mut R) -> Result<Vec<u8>> {
+        let size = reader.seek(SeekFrom::End(0))?;
+        if let Some(limit) = self.options.memory_limit {
+            if size > limit {
+                return Err(ValidationError::MemoryLimit { needed: size, limit });
             }
-            // Fallback to basic validation
-            validate_pdf_basic(path)
         }
+        reader.seek(SeekFrom::Start(0))?;
+        let mut data = Vec::with_capacity(size as usize);
+        reader.read_to_end(&mut data)?;
+        Ok(data)
     }
-}
 
-/// Validate PDF using pdf-rs library (pure Rust, thread-safe)
-pub fn validate_pdf_with_pdf_rs(path: &Path) -> Result<bool> {
-    // Check circuit breaker first
-    if CIRCUIT_BREAKER.is_open() {
-        anyhow::bail!("Circuit breaker is OPEN - too many recent failures");
-    }
-    
-    // pdf-rs is thread-safe, no semaphore needed
-    match pdf::file::FileOptions::cached().open(path) {
-        Ok(pdf_file) => {
-            CIRCUIT_BREAKER.record_success();
-            
-            // Check if document has pages
-            let num_pages = pdf_file.num_pages();
-            if num_pages == 0 {
-                Ok(false)
-            } else {
-                // Verify we can actually access at least one page
-                match pdf_file.get_page(0) {
-                    Ok(_) => Ok(true),
-                    Err(_) => Ok(false),
+    fn validate_source(&self, source: Source<'_>, strictness: Strictness) -> ValidationOutcome {
+        match self.options.timeout {
+            Some(limit) => self.validate_with_timeout(source, strictness, limit),
+            None => self.run_chain(source, strictness),
+        }
+    }
+
+    /// Run the chain on a watchdog-supervised thread
+    ///
+    /// A thread stuck inside pdf-rs cannot be cancelled, so on timeout it is
+    /// left to finish in the background and its result is discarded. At
+    /// most [`MAX_ABANDONED_THREADS`] such threads are left running; past
+    /// that, files are reported as timed out without being validated until
+    /// some of them finish. Use isolated workers when stuck files must
+    /// actually be killed.
+    fn validate_with_timeout(&self, source: Source<'_>, strictness: Strictness, limit: Duration) -> ValidationOutcome {
+        let abandoned = ABANDONED_THREADS.load(Ordering::SeqCst);
+        if abandoned >= MAX_ABANDONED_THREADS {
+            let mut outcome = ValidationOutcome::timed_out(limit);
+            outcome.message = Some(format!(
+                "Not validated: {} earlier files are still running past the {:?} time limit",
+                abandoned, limit
+            ));
+            return outcome;
+        }
+
+        let (sender, receiver) = mpsc::channel();
+        let validator = self.clone();
+        let owned_source = source.into_owned();
+        let state = Arc::new(AtomicU8::new(RUNNING));
+        let thread_state = state.clone();
+
+        let handle = match thread::Builder::new()
+            .name("pdf-validate".to_string())
+            .spawn(move || {
+                let _ = sender.send(validator.run_chain(owned_source.as_source(), strictness));
+                if thread_state.swap(FINISHED, Ordering::SeqCst) == ABANDONED {
+                    ABANDONED_THREADS.fetch_sub(1, Ordering::SeqCst);
+                }
+            }) {
+            Ok(handle) => handle,
+            // No thread to supervise; validate without a time limit
+            Err(_) => return self.run_chain(source, strictness),
+        };
+
+        match receiver.recv_timeout(limit) {
+            Ok(outcome) => outcome,
+            Err(RecvTimeoutError::Timeout) => {
+                // Counted first so a thread finishing right now never takes the count below zero
+                ABANDONED_THREADS.fetch_add(1, Ordering::SeqCst);
+                if state.compare_exchange(RUNNING, ABANDONED, Ordering::SeqCst, Ordering::SeqCst).is_err() {
+                    ABANDONED_THREADS.fetch_sub(1, Ordering::SeqCst);
+                }
+                ValidationOutcome::timed_out(limit)
+            }
+            Err(RecvTimeoutError::Disconnected) => match handle.join() {
+                Err(payload) => ValidationOutcome::panicked(panic_message(payload.as_ref())),
+                Ok(()) => ValidationOutcome::panicked("validation thread exited without an outcome"),
+            },
+        }
+    }
+
+    /// Run the chain, measured against the memory budget if one is set
+    ///
+    /// The header is read here rather than by the caller so that it is
+    /// covered by the timeout; opening a FIFO or a stalled network file can
+    /// block.
+    fn run_chain(&self, source: Source<'_>, strictness: Strictness) -> ValidationOutcome {
+        let mut outcome = self.run_budgeted(source, strictness);
+        outcome.pdf_version = header_version(source);
+        outcome
+    }
+
+    fn run_budgeted(&self, source: Source<'_>, strictness: Strictness) -> ValidationOutcome {
+        match self.options.memory_limit {
+            Some(limit) => {
+                let budget = MemoryBudget::start(limit);
+                let outcome = self.run_strategies(source, strictness);
+                if budget.exceeded() {
+                    ValidationOutcome::memory_limit_exceeded(limit)
+                } else {
+                    outcome
+                }
+            }
+            None => self.run_strategies(source, strictness),
+        }
+    }
+
+    fn run_strategies(&self, source: Source<'_>, strictness: Strictness) -> ValidationOutcome {
+        let mut first_failure = None;
+        let quick_result = match source {
+            Source::Path(path) => self.quick_validate(path),
+            Source::Bytes(data) => self.quick_check(&mut Cursor::new(data)),
+        };
+        let quick_passed = match quick_result {
+            Ok(()) => true,
+            Err(e) => {
+                let failure = failure_outcome(ValidationStage::QuickCheck, &e);
+                if strictness == Strictness::Strict {
+                    return failure;
+                }
+                first_failure = Some(failure);
+                false
+            }
+        };
+
+        for &strategy in &self.options.strategies {
+            // Full parsers are only worth running on files that look like PDFs
+            if strategy == Strategy::PdfRs && !quick_passed {
+                continue;
+            }
+
+            let result = match catch_panic(|| self.run_strategy(strategy, source)) {
+                Ok(result) => result,
+                Err(message) => {
+                    self.circuit_breaker.record_failure();
+                    return ValidationOutcome::panicked(format!("{} strategy panicked: {}", strategy, message));
+                }
+            };
+
+            match result {
+                Ok(pages) => {
+                    let mut outcome = match first_failure {
+                        None => ValidationOutcome::valid(strategy),
+                        Some(failure) => ValidationOutcome::valid_with_warnings(strategy, failure),
+                    };
+                    outcome.pages = pages;
+                    return outcome;
+                }
+                Err(e) => {
+                    let is_final = e.is_structural() && strictness == Strictness::Strict;
+                    let failure = match strategy {
+                        Strategy::Basic => ValidationOutcome::failure(
+                            e.category(),
+                            ValidationStage::Basic,
+                            e.to_string(),
+                        ),
+                        _ => failure_outcome(ValidationStage::Parse, &e),
+                    };
+                    first_failure.get_or_insert(failure);
+                    if is_final {
+                        break;
+                    }
+                }
+            }
+        }
+
+        // The chain is never empty, so reaching here means something failed
+        first_failure.expect("strategy chain produced no outcome")
+    }
+
+    /// Run one strategy, returning the page count if it was read
+    fn run_strategy(&self, strategy: Strategy, source: Source<'_>) -> Result<Option<u32>> {
+        match strategy {
+            Strategy::PdfRs => {
+                if let (Some(hook), Source::Path(path)) = (&self.parse_hook, source) {
+                    hook(path);
+                }
+                self.parse_source(source).map(Some)
+            }
+            Strategy::Basic => {
+                self.check_fits_in_memory(source)?;
+                match source {
+                    Source::Path(path) => basic_check(path),
+                    Source::Bytes(data) => basic_check_bytes(data),
+                }
+                .map(|()| None)
+            }
+        }
+    }
+
+    /// Refuse to load documents larger than the memory budget
+    ///
+    /// pdf-rs and the stream and basic checks hold the whole file in memory.
+    fn check_fits_in_memory(&self, source: Source<'_>) -> Result<()> {
+        if let Some(limit) = self.options.memory_limit {
+            let size = source.len()?;
+            if size > limit {
+                return Err(ValidationError::MemoryLimit { needed: size, limit });
+            }
+        }
+        Ok(())
+    }
+
+    /// Quick pre-validation before attempting full parse
+    /// Checks PDF magic bytes, file size, and EOF marker
+    pub fn quick_validate(&self, path: &Path) -> Result<()> {
+        self.quick_check(&mut File::open(path)?)
+    }
+
+    fn quick_check<R: Read + Seek>(&self, reader: &mut R) -> Result<()> {
+        let file_size = reader.seek(SeekFrom::End(0))?;
+        reader.seek(SeekFrom::Start(0))?;
+
+        // 1. Check PDF magic bytes (%PDF-), allowing the same leading junk as discovery
+        let mut prefix = Vec::with_capacity(SNIFF_WINDOW);
+        reader.take(SNIFF_WINDOW as u64).read_to_end(&mut prefix)?;
+        if prefix.len() < 8 {
+            return Err(ValidationError::TooSmall { size: file_size, min: self.options.min_file_size });
+        }
+        if find_pdf_header(&prefix).is_none() {
+            return Err(ValidationError::InvalidHeader);
+        }
+
+        // 2. Check file size
+        if file_size > self.options.max_file_size {
+            return Err(ValidationError::TooLarge { size: file_size, limit: self.options.max_file_size });
+        }
+
+        if file_size < self.options.min_file_size {
+            return Err(ValidationError::TooSmall { size: file_size, min: self.options.min_file_size });
+        }
+
+        // 3. Check for EOF marker (%%EOF) in the trailing window
+        let tail_size = std::cmp::min(self.options.eof_search_window, file_size);
+        reader.seek(SeekFrom::End(-(tail_size as i64)))?;
+        let mut tail = vec![0u8; tail_size as usize];
+        reader.read_exact(&mut tail)?;
+
+        if !tail.windows(5).any(|w| w == b"%%EOF") {
+            return Err(ValidationError::MissingEof);
+        }
+
+        Ok(())
+    }
+
+    /// Parse with pdf-rs and check the page tree
+    ///
+    /// At the standard level only the first page is loaded; at the deep
+    /// level every page is resolved (see [`check_page_tree`]).
+    pub fn parse_check(&self, path: &Path) -> Result<()> {
+        self.parse_source(Source::Path(path)).map(|_| ())
+    }
+
+    /// Parse and check the page tree, returning the page count
+    fn parse_source(&self, source: Source<'_>) -> Result<u32> {
+        // Check circuit breaker first
+        if self.circuit_breaker.is_open() {
+            return Err(ValidationError::CircuitOpen);
+        }
+        self.check_fits_in_memory(source)?;
+
+        // pdf-rs is thread-safe, no semaphore needed
+        let parsed = Document::read(source)
+            .and_then(|document| Ok((pdf::file::FileOptions::cached().load(document.clone())?, document)));
+
+        match parsed {
+            Ok((pdf_file, document)) => {
+                self.circuit_breaker.record_success();
+
+                // Check if document has pages
+                if pdf_file.num_pages() == 0 {
+                    return Err(ValidationError::NoPages);
+                }
+
+                match self.options.level {
+                    // Verify we can actually access at least one page
+                    ValidationLevel::Standard => pdf_file
+                        .get_page(0)
+                        .map(|_| ())
+                        .map_err(|source| ValidationError::PageLoad { page: 0, source })?,
+                    ValidationLevel::Deep => check_page_tree(&pdf_file)?,
                 }
+                if self.options.verify_streams {
+                    self.check_streams(&pdf_file, &document)?;
+                }
+                Ok(pdf_file.num_pages())
+            }
+            Err(e) => {
+                self.circuit_breaker.record_failure();
+                Err(ValidationError::from_pdf(e))
             }
         }
-        Err(e) => {
-            CIRCUIT_BREAKER.record_failure();
-            anyhow::bail!("pdf-rs parse error: {}", e)
+    }
+
+    /// Check every stream's `/Length` and decode it through its filters
+    pub fn stream_check(&self, path: &Path) -> Result<()> {
+        self.check_fits_in_memory(Source::Path(path))?;
+        let document = Document::read(Source::Path(path)).map_err(ValidationError::from_pdf)?;
+        let pdf_file = pdf::file::FileOptions::cached()
+            .load(document.clone())
+            .map_err(ValidationError::from_pdf)?;
+        self.check_streams(&pdf_file, &document)
+    }
+
+    /// Verify the streams of `document`, which pdf-rs has already parsed
+    fn check_streams(&self, pdf_file: &ParsedFile, document: &[u8]) -> Result<()> {
+        let limit = self.options.memory_limit.unwrap_or(u64::MAX);
+        let failures = verify_streams_within(pdf_file, document, limit)
+            .map_err(|needed| ValidationError::MemoryLimit { needed, limit })?;
+        if failures.is_empty() {
+            Ok(())
+        } else {
+            Err(ValidationError::BrokenStreams { streams: failures })
         }
     }
 }
 
-/// Basic PDF validation (fallback when pdf-rs fails)
-pub fn validate_pdf_basic(path: &Path) -> bool {
-    let mut file = match File::open(path) {
-        Ok(f) => f,
-        Err(_) => return false,
+/// Builder for [`Validator`]
+#[derive(Debug, Clone, Default)]
+pub struct ValidatorBuilder {
+    options: ValidationOptions,
+}
+
+impl ValidatorBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replace all options at once
+    pub fn options(mut self, options: ValidationOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    pub fn max_file_size(mut self, bytes: u64) -> Self {
+        self.options.max_file_size = bytes;
+        self
+    }
+
+    pub fn min_file_size(mut self, bytes: u64) -> Self {
+        self.options.min_file_size = bytes;
+        self
+    }
+
+    pub fn eof_search_window(mut self, bytes: u64) -> Self {
+        self.options.eof_search_window = bytes;
+        self
+    }
+
+    pub fn strictness(mut self, strictness: Strictness) -> Self {
+        self.options.strictness = strictness;
+        self
+    }
+
+    pub fn level(mut self, level: ValidationLevel) -> Self {
+        self.options.level = level;
+        self
+    }
+
+    pub fn verify_streams(mut self, verify: bool) -> Self {
+        self.options.verify_streams = verify;
+        self
+    }
+
+    pub fn timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.options.timeout = timeout;
+        self
+    }
+
+    pub fn memory_limit(mut self, bytes: Option<u64>) -> Self {
+        self.options.memory_limit = bytes;
+        self
+    }
+
+    pub fn strategies(mut self, strategies: Vec<Strategy>) -> Self {
+        self.options.strategies = strategies;
+        self
+    }
+
+    pub fn circuit_breaker(mut self, failure_threshold: usize, cooldown: Duration) -> Self {
+        self.options.circuit_breaker_threshold = failure_threshold;
+        self.options.circuit_breaker_cooldown = cooldown;
+        self
+    }
+
+    pub fn build(self) -> Validator {
+        Validator::new(self.options)
+    }
+}
+
+/// Resolve every page of a parsed document
+///
+/// The page tree is walked kid by kid, resolving each `/Kids` entry on its
+/// own, so a broken page is reported once instead of also failing every
+/// page after it. For each page its `/MediaBox`, its (possibly inherited)
+/// `/Resources` and the operations of its `/Contents` streams are resolved.
+/// All failing pages are collected rather than stopping at the first one.
+fn check_page_tree(pdf_file: &ParsedFile) -> Result<()> {
+    let resolver = pdf_file.resolver();
+    let mut failures = Vec::new();
+    let mut index = 0;
+    check_kids(&pdf_file.get_root().pages, &resolver, PAGE_TREE_DEPTH, &mut index, &mut failures);
+
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        Err(ValidationError::BrokenPages { pages: failures })
+    }
+}
+
+/// Nesting limit for page tree nodes, matching pdf-rs
+const PAGE_TREE_DEPTH: usize = 16;
+
+/// Check the kids of one page tree node, numbering pages from `*index`
+fn check_kids(
+    tree: &pdf::object::PageTree,
+    resolver: &impl pdf::object::Resolve,
+    depth: usize,
+    index: &mut u32,
+    failures: &mut Vec<PageFailure>,
+) {
+    use pdf::object::PagesNode;
+
+    if depth == 0 {
+        failures.push(PageFailure { page: *index, message: "page tree nested too deeply".to_string() });
+        *index += tree.count;
+        return;
+    }
+
+    for &kid in &tree.kids {
+        match resolver.get(kid) {
+            Ok(node) => match *node {
+                PagesNode::Tree(ref subtree) => check_kids(subtree, resolver, depth - 1, index, failures),
+                PagesNode::Leaf(ref page) => {
+                    if let Err(message) = check_page(page, resolver) {
+                        failures.push(PageFailure { page: *index, message });
+                    }
+                    *index += 1;
+                }
+            },
+            Err(e) => {
+                failures.push(PageFailure { page: *index, message: format!("page object: {}", e) });
+                // Keep later page numbers right when the broken kid is a subtree
+                *index += kid_page_count(kid.get_inner(), resolver);
+            }
+        }
+    }
+}
+
+/// Pages below a kid that failed to load: its `/Count` if it is an
+/// intermediate node that can still be read, otherwise one
+fn kid_page_count(kid: pdf::object::PlainRef, resolver: &impl pdf::object::Resolve) -> u32 {
+    use pdf::primitive::Primitive;
+
+    let Ok(Primitive::Dictionary(dict)) = resolver.resolve(kid) else {
+        return 1;
     };
+    let is_tree = matches!(dict.get("Type"), Some(Primitive::Name(name)) if name.as_str() == "Pages");
+    match dict.get("Count") {
+        Some(&Primitive::Integer(count)) if is_tree && count > 0 => count as u32,
+        _ => 1,
+    }
+}
 
-    let mut buffer = Vec::new();
-    if file.read_to_end(&mut buffer).is_err() {
-        return false;
+fn check_page(page: &pdf::object::Page, resolver: &impl pdf::object::Resolve) -> std::result::Result<(), String> {
+    page.media_box().map_err(|e| format!("/MediaBox: {}", e))?;
+    page.resources().map_err(|e| format!("/Resources: {}", e))?;
+    if let Some(ref contents) = page.contents {
+        contents.operations(resolver).map_err(|e| format!("/Contents: {}", e))?;
     }
+    Ok(())
+}
+
+/// Header and `%%EOF` scan of the whole file
+fn basic_check(path: &Path) -> Result<()> {
+    let mut buffer = Vec::new();
+    File::open(path)?.read_to_end(&mut buffer)?;
+    basic_check_bytes(&buffer)
+}
 
+fn basic_check_bytes(buffer: &[u8]) -> Result<()> {
     // Check for PDF header
-    if buffer.len() < 5 || &buffer[0..5] != b"%PDF-" {
-        return false;
+    if find_pdf_header(buffer).is_none() {
+        return Err(ValidationError::InvalidHeader);
     }
 
     // Check for EOF marker
-    buffer.windows(5).any(|window| window == b"%%EOF")
+    if !buffer.windows(5).any(|window| window == b"%%EOF") {
+        return Err(ValidationError::MissingEof);
+    }
+
+    Ok(())
+}
+
+/// Validate a PDF file and report why it failed
+///
+/// Uses the default [`Validator`]: quick pre-checks, then pdf-rs, with the
+/// basic header/EOF scan as a fallback when pdf-rs cannot parse the file.
+pub fn validate_pdf_outcome(path: &Path) -> ValidationOutcome {
+    DEFAULT_VALIDATOR.validate(path)
+}
+
+/// Validate a PDF held in memory with the default [`Validator`]
+pub fn validate_bytes(data: &[u8]) -> ValidationOutcome {
+    DEFAULT_VALIDATOR.validate_bytes(data)
+}
+
+/// Validate a PDF read from `reader` with the default [`Validator`]
+pub fn validate_reader<R: Read + Seek>(reader: R) -> ValidationOutcome {
+    DEFAULT_VALIDATOR.validate_reader(reader)
+}
+
+/// Lenient variant of [`validate_pdf_outcome`]
+///
+/// Any failure, including a failed quick check, falls back to the basic scan.
+pub fn validate_pdf_lenient_outcome(path: &Path) -> ValidationOutcome {
+    DEFAULT_VALIDATOR.validate_with_strictness(path, Strictness::Lenient)
+}
+
+/// Validate a PDF file
+///
+/// # Arguments
+/// * `path` - Path to the PDF file
+/// * `verbose` - Whether to print verbose error messages
+///
+/// # Returns
+/// `true` if the PDF is valid, `false` otherwise
+pub fn validate_pdf(path: &Path, verbose: bool) -> bool {
+    let outcome = validate_pdf_outcome(path);
+    if verbose && outcome.verdict != Verdict::Valid {
+        eprintln!("{:?}: {}", path, outcome);
+    }
+    outcome.is_valid()
+}
+
+/// Validate PDF using pdf-rs library (pure Rust, thread-safe)
+///
+/// Returns `Ok(false)` when the document parses but its pages are unusable.
+pub fn validate_pdf_with_pdf_rs(path: &Path) -> Result<bool> {
+    match DEFAULT_VALIDATOR.parse_check(path) {
+        Ok(()) => Ok(true),
+        Err(e) if e.is_structural() => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+/// Basic PDF validation (fallback when pdf-rs fails)
+pub fn validate_pdf_basic(path: &Path) -> bool {
+    basic_check(path).is_ok()
 }
 
 /// Validate PDF with detailed error information
 pub fn validate_pdf_detailed(path: &Path) -> Result<bool> {
     // Quick pre-validation
-    quick_validate(path)?;
-    
-    // Check circuit breaker
-    if CIRCUIT_BREAKER.is_open() {
-        anyhow::bail!("Circuit breaker is OPEN - too many recent failures");
-    }
-    
-    // pdf-rs is thread-safe, no semaphore needed
-    match pdf::file::FileOptions::cached().open(path) {
-        Ok(pdf_file) => {
-            CIRCUIT_BREAKER.record_success();
-            
-            let num_pages = pdf_file.num_pages();
-            if num_pages == 0 {
-                anyhow::bail!("PDF has no pages")
-            }
-            Ok(true)
-        }
-        Err(e) => {
-            CIRCUIT_BREAKER.record_failure();
-            Err(e.into())
-        }
-    }
+    DEFAULT_VALIDATOR.quick_validate(path)?;
+    DEFAULT_VALIDATOR.parse_check(path)?;
+    Ok(true)
 }
 
 /// Lenient PDF validation that tries multiple strategies
 pub fn validate_pdf_lenient(path: &Path) -> bool {
-    // Try quick validation first
-    if quick_validate(path).is_err() {
-        // If quick validation fails, still try basic validation
-        return validate_pdf_basic(path);
-    }
-    
-    // Try pdf-rs (thread-safe, pure Rust)
-    if let Ok(true) = validate_pdf_with_pdf_rs(path) {
-        return true;
-    }
-    
-    // Fallback to basic
-    validate_pdf_basic(path)
+    validate_pdf_lenient_outcome(path).is_valid()
 }
 
 #[cfg(feature = "rendering")]
@@ -0,0 +1,13 @@
+//! Subprocess isolation for untrusted PDFs
+//!
+//! Parsing hostile files in-process can abort the whole run (double frees,
+//! allocator panics, stack overflows). In isolation mode each file is
+//! validated by a worker subprocess; a worker that crashes only costs the
+//! file it was working on, and is replaced by a fresh one.
+
+pub mod pool;
+pub mod protocol;
+pub mod worker;
+
+pub use pool::{WorkerCommand, WorkerPool};
+pub use worker::run_worker;
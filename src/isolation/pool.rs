@@ -0,0 +1,287 @@
+//! Pool of worker subprocesses
+
+use std::io::{self, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdin, Command, ExitStatus, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::{Condvar, Mutex, PoisonError};
+use std::thread;
+use std::time::Duration;
+
+use super::protocol::{read_frame, write_frame, WorkerRequest};
+use crate::core::memory::MEMORY_LIMIT_EXIT_CODE;
+use crate::core::options::ValidationOptions;
+use crate::core::outcome::ValidationOutcome;
+
+/// How to launch a worker process
+#[derive(Debug, Clone)]
+pub struct WorkerCommand {
+    pub program: PathBuf,
+    pub args: Vec<String>,
+}
+
+impl WorkerCommand {
+    /// Re-execute the running binary with its hidden `worker` subcommand
+    pub fn current_exe() -> io::Result<Self> {
+        Ok(Self {
+            program: std::env::current_exe()?,
+            args: vec!["worker".to_string()],
+        })
+    }
+}
+
+/// What a worker is asked to validate
+#[derive(Clone, Copy)]
+enum Job<'a> {
+    File(&'a Path),
+    /// A document held in memory, labelled with the path it is reported under
+    Bytes(&'a Path, &'a [u8]),
+}
+
+/// Why a request did not produce an outcome
+enum RequestError {
+    TimedOut,
+    /// The worker is gone; its exit status if it could be collected, and
+    /// the error that revealed the death
+    Died(Option<ExitStatus>, String),
+}
+
+/// A running worker subprocess
+struct Worker {
+    child: Child,
+    stdin: ChildStdin,
+    responses: Receiver<io::Result<ValidationOutcome>>,
+}
+
+impl Worker {
+    fn spawn(command: &WorkerCommand, options: &ValidationOptions) -> io::Result<Self> {
+        let mut child = Command::new(&command.program)
+            .args(&command.args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .spawn()?;
+
+        let mut stdin = child.stdin.take().expect("worker stdin is piped");
+        let stdout = child.stdout.take().expect("worker stdout is piped");
+
+        if let Err(e) = write_frame(&mut stdin, options) {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(e);
+        }
+
+        // Responses are read on a separate thread so requests can time out
+        let (sender, responses) = mpsc::channel();
+        let reader = thread::Builder::new()
+            .name("pdf-worker-reader".to_string())
+            .spawn(move || {
+                let mut stdout = BufReader::new(stdout);
+                loop {
+                    let response = match read_frame(&mut stdout) {
+                        Ok(Some(outcome)) => Ok(outcome),
+                        Ok(None) => break,
+                        Err(e) => Err(e),
+                    };
+                    let failed = response.is_err();
+                    if sender.send(response).is_err() || failed {
+                        break;
+                    }
+                }
+            });
+        if let Err(e) = reader {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(e);
+        }
+
+        Ok(Self {
+            child,
+            stdin,
+            responses,
+        })
+    }
+
+    fn request(&mut self, job: Job, timeout: Option<Duration>) -> Result<ValidationOutcome, RequestError> {
+        let sent = match job {
+            Job::File(path) => write_frame(
+                &mut self.stdin,
+                &WorkerRequest {
+                    path: path.to_path_buf(),
+                    inline_len: None,
+                },
+            ),
+            Job::Bytes(label, data) => write_frame(
+                &mut self.stdin,
+                &WorkerRequest {
+                    path: label.to_path_buf(),
+                    inline_len: Some(data.len() as u64),
+                },
+            )
+            .and_then(|()| self.stdin.write_all(data))
+            .and_then(|()| self.stdin.flush()),
+        };
+        if let Err(e) = sent {
+            return Err(RequestError::Died(self.reap(), e.to_string()));
+        }
+
+        let response = match timeout {
+            Some(limit) => match self.responses.recv_timeout(limit) {
+                Ok(response) => Some(response),
+                Err(RecvTimeoutError::Timeout) => return Err(RequestError::TimedOut),
+                Err(RecvTimeoutError::Disconnected) => None,
+            },
+            None => self.responses.recv().ok(),
+        };
+
+        match response {
+            Some(Ok(outcome)) => Ok(outcome),
+            Some(Err(e)) => Err(RequestError::Died(self.reap(), e.to_string())),
+            None => Err(RequestError::Died(self.reap(), "Worker closed its output".to_string())),
+        }
+    }
+
+    /// Make sure the worker is gone and collect its exit status
+    fn reap(&mut self) -> Option<ExitStatus> {
+        let _ = self.child.kill();
+        self.child.wait().ok()
+    }
+
+    /// Close the worker's input and wait for it to exit
+    fn shutdown(self) {
+        let Worker { mut child, stdin, .. } = self;
+        drop(stdin);
+        let _ = child.wait();
+    }
+}
+
+fn describe_exit(status: ExitStatus) -> String {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        if let Some(signal) = status.signal() {
+            return format!("Worker killed by signal {}", signal);
+        }
+    }
+    format!("Worker exited with {}", status)
+}
+
+struct PoolState {
+    idle: Vec<Worker>,
+    /// Workers currently alive, idle or busy
+    live: usize,
+}
+
+/// Validates files in a bounded set of worker subprocesses
+///
+/// Workers are started on demand up to `size`. A worker that crashes,
+/// aborts or is killed marks only its current file as `Crashed`; a
+/// worker that exceeds the per-file timeout is killed and the file is
+/// marked `TimedOut`, and one that runs out of its memory limit marks the
+/// file `ResourceLimitExceeded`. Either way a fresh worker takes its place.
+pub struct WorkerPool {
+    command: WorkerCommand,
+    options: ValidationOptions,
+    size: usize,
+    state: Mutex<PoolState>,
+    available: Condvar,
+}
+
+impl WorkerPool {
+    /// Create a pool of up to `size` workers validating with `options`
+    pub fn new(command: WorkerCommand, options: ValidationOptions, size: usize) -> Self {
+        Self {
+            command,
+            options,
+            size: size.max(1),
+            state: Mutex::new(PoolState {
+                idle: Vec::new(),
+                live: 0,
+            }),
+            available: Condvar::new(),
+        }
+    }
+
+    /// Validate one file in a worker process
+    pub fn validate(&self, path: &Path) -> ValidationOutcome {
+        self.run(Job::File(path))
+    }
+
+    /// Validate a document held in memory in a worker process
+    ///
+    /// `label` names the document, as the path of an archive member does;
+    /// the data itself is sent to the worker.
+    pub fn validate_bytes(&self, label: &Path, data: &[u8]) -> ValidationOutcome {
+        self.run(Job::Bytes(label, data))
+    }
+
+    fn run(&self, job: Job) -> ValidationOutcome {
+        let mut worker = match self.checkout() {
+            Ok(worker) => worker,
+            Err(e) => return ValidationOutcome::crashed(format!("Failed to start worker: {}", e)),
+        };
+
+        match worker.request(job, self.options.timeout) {
+            Ok(outcome) => {
+                self.checkin(worker);
+                outcome
+            }
+            Err(RequestError::TimedOut) => {
+                worker.reap();
+                self.retire();
+                ValidationOutcome::timed_out(self.options.timeout.unwrap_or_default())
+            }
+            Err(RequestError::Died(status, error)) => {
+                self.retire();
+                self.death_outcome(status, error)
+            }
+        }
+    }
+
+    fn death_outcome(&self, status: Option<ExitStatus>, error: String) -> ValidationOutcome {
+        match status {
+            Some(status) if status.code() == Some(MEMORY_LIMIT_EXIT_CODE) => {
+                ValidationOutcome::memory_limit_exceeded(self.options.memory_limit.unwrap_or_default())
+            }
+            Some(status) => ValidationOutcome::crashed(describe_exit(status)),
+            None => ValidationOutcome::crashed(error),
+        }
+    }
+
+    fn checkout(&self) -> io::Result<Worker> {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        loop {
+            if let Some(worker) = state.idle.pop() {
+                return Ok(worker);
+            }
+            if state.live < self.size {
+                state.live += 1;
+                drop(state);
+                return Worker::spawn(&self.command, &self.options).inspect_err(|_| self.retire());
+            }
+            state = self.available.wait(state).unwrap_or_else(PoisonError::into_inner);
+        }
+    }
+
+    fn checkin(&self, worker: Worker) {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        state.idle.push(worker);
+        self.available.notify_one();
+    }
+
+    /// Forget a dead worker so a replacement can be started
+    fn retire(&self) {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        state.live -= 1;
+        self.available.notify_one();
+    }
+}
+
+impl Drop for WorkerPool {
+    fn drop(&mut self) {
+        let state = self.state.get_mut().unwrap_or_else(PoisonError::into_inner);
+        for worker in state.idle.drain(..) {
+            worker.shutdown();
+        }
+    }
+}
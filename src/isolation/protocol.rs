@@ -0,0 +1,81 @@
+//! Framing between the parent process and its workers
+//!
+//! Every message is a 4-byte big-endian length followed by a JSON payload.
+//! The parent first sends the worker its [`ValidationOptions`], then one
+//! [`WorkerRequest`] per file; the worker answers each request with a
+//! [`ValidationOutcome`](crate::core::outcome::ValidationOutcome). A
+//! request for a document held in memory, such as an archive member, is
+//! followed by the document's raw bytes.
+//!
+//! [`ValidationOptions`]: crate::core::options::ValidationOptions
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::io::{self, ErrorKind, Read, Write};
+use std::path::PathBuf;
+
+/// Upper bound on a single frame, guarding against a corrupted stream
+const MAX_FRAME_LEN: usize = 64 * 1024 * 1024;
+
+/// A file the worker should validate
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkerRequest {
+    pub path: PathBuf,
+    /// Length of an in-memory document sent right after the request, in
+    /// which case `path` is only a label
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub inline_len: Option<u64>,
+}
+
+/// Write one length-prefixed JSON frame and flush
+pub fn write_frame<W: Write, T: Serialize>(writer: &mut W, value: &T) -> io::Result<()> {
+    let payload = serde_json::to_vec(value)?;
+    writer.write_all(&(payload.len() as u32).to_be_bytes())?;
+    writer.write_all(&payload)?;
+    writer.flush()
+}
+
+/// Read one frame, returning `None` on a clean end of stream
+pub fn read_frame<R: Read, T: DeserializeOwned>(reader: &mut R) -> io::Result<Option<T>> {
+    let mut len = [0u8; 4];
+    match reader.read_exact(&mut len) {
+        Ok(()) => {}
+        Err(e) if e.kind() == ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e),
+    }
+
+    let len = u32::from_be_bytes(len) as usize;
+    if len > MAX_FRAME_LEN {
+        return Err(io::Error::new(ErrorKind::InvalidData, format!("frame of {} bytes too large", len)));
+    }
+
+    let mut payload = vec![0u8; len];
+    reader.read_exact(&mut payload)?;
+    Ok(Some(serde_json::from_slice(&payload)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_frame_round_trip() {
+        let mut buffer = Vec::new();
+        let request = WorkerRequest {
+            path: PathBuf::from("/tmp/a.pdf"),
+            inline_len: None,
+        };
+        write_frame(&mut buffer, &request).unwrap();
+        write_frame(&mut buffer, &request).unwrap();
+
+        let mut reader = Cursor::new(buffer);
+        let first: Option<WorkerRequest> = read_frame(&mut reader).unwrap();
+        let second: Option<WorkerRequest> = read_frame(&mut reader).unwrap();
+        let end: Option<WorkerRequest> = read_frame(&mut reader).unwrap();
+
+        assert_eq!(first.unwrap().path, PathBuf::from("/tmp/a.pdf"));
+        assert!(second.is_some());
+        assert!(end.is_none());
+    }
+}
@@ -0,0 +1,90 @@
+//! Worker side of isolation mode
+
+use std::io::{Read, Write};
+
+use super::protocol::{read_frame, write_frame, WorkerRequest};
+use crate::core::error::Result;
+use crate::core::memory::limit_address_space;
+use crate::core::options::ValidationOptions;
+use crate::core::validator::Validator;
+
+/// Serve validation requests until the parent closes the input stream
+///
+/// The first frame carries the [`ValidationOptions`]. Time limits are
+/// enforced by the parent, which kills workers that overrun, so the
+/// worker validates without one. A memory limit caps the worker's
+/// address space. Documents sent inline are validated from memory.
+pub fn run_worker<R: Read, W: Write>(mut input: R, mut output: W) -> Result<()> {
+    let mut options: ValidationOptions = match read_frame(&mut input)? {
+        Some(options) => options,
+        None => return Ok(()),
+    };
+    options.timeout = None;
+    if let Some(limit) = options.memory_limit {
+        limit_address_space(limit)?;
+    }
+    let validator = Validator::new(options);
+
+    while let Some(request) = read_frame::<_, WorkerRequest>(&mut input)? {
+        let outcome = match request.inline_len {
+            Some(len) => {
+                let mut data = vec![0u8; len as usize];
+                input.read_exact(&mut data)?;
+                validator.validate_bytes(&data)
+            }
+            None => validator.validate(&request.path),
+        };
+        write_frame(&mut output, &outcome)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::outcome::{ValidationOutcome, Verdict};
+    use std::io::Cursor;
+
+    #[test]
+    fn test_worker_answers_each_request() {
+        let mut input = Vec::new();
+        write_frame(&mut input, &ValidationOptions::default()).unwrap();
+        for _ in 0..2 {
+            let request = WorkerRequest {
+                path: "/nonexistent/file.pdf".into(),
+                inline_len: None,
+            };
+            write_frame(&mut input, &request).unwrap();
+        }
+
+        let mut output = Vec::new();
+        run_worker(Cursor::new(input), &mut output).unwrap();
+
+        let mut responses = Cursor::new(output);
+        for _ in 0..2 {
+            let outcome: ValidationOutcome = read_frame(&mut responses).unwrap().unwrap();
+            assert_eq!(outcome.verdict, Verdict::Errored);
+        }
+        assert!(read_frame::<_, ValidationOutcome>(&mut responses).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_worker_validates_inline_documents() {
+        let data = b"not a pdf at all";
+        let mut input = Vec::new();
+        write_frame(&mut input, &ValidationOptions::default()).unwrap();
+        let request = WorkerRequest {
+            path: "bundle.zip!/a.pdf".into(),
+            inline_len: Some(data.len() as u64),
+        };
+        write_frame(&mut input, &request).unwrap();
+        input.extend_from_slice(data);
+
+        let mut output = Vec::new();
+        run_worker(Cursor::new(input), &mut output).unwrap();
+
+        let outcome: ValidationOutcome = read_frame(&mut Cursor::new(output)).unwrap().unwrap();
+        assert_eq!(outcome.verdict, Verdict::Invalid);
+    }
+}
@@ -2,7 +2,9 @@
 //! 
 //! A high-performance PDF validation library with support for parallel processing.
 
+pub mod cache;
 pub mod core;
+pub mod isolation;
 pub mod scanner;
 pub mod reporting;
 
@@ -15,8 +17,29 @@ pub mod prelude {
     pub use crate::core::validator::{
         validate_pdf, validate_pdf_with_pdf_rs, validate_pdf_basic,
         validate_pdf_detailed, validate_pdf_lenient, // validate_pdf_rendering
+        validate_pdf_outcome, validate_pdf_lenient_outcome, validate_bytes, validate_reader, catch_panic, Validator, ValidatorBuilder,
+    };
+    pub use crate::core::options::{Strictness, ValidationLevel, ValidationOptions};
+    pub use crate::core::error::ValidationError;
+    pub use crate::core::memory::{MemoryBudget, TrackingAllocator};
+    pub use crate::core::outcome::{
+        FailureCategory, PageFailure, Strategy, StreamFailure, ValidationOutcome, ValidationStage, Verdict,
+    };
+    pub use crate::cache::{cache_fingerprint, Checkpoint, Checkpointer, ResultCache};
+    pub use crate::isolation::{WorkerCommand, WorkerPool};
+    pub use crate::scanner::file_scanner::{
+        collect_pdf_files, scan, scan_parallel, DiscoveredFile, ScanStream, ValidationResult,
+    };
+    pub use crate::scanner::scan_options::{Discovery, ScanOptions};
+    pub use crate::scanner::content_sniffer::{find_pdf_header, sniff_pdf, Mislabel};
+    pub use crate::scanner::file_list::{parse_file_list, read_file_list};
+    pub use crate::scanner::archive::{validate_archive, validate_archive_with, ArchiveKind};
+    pub use crate::scanner::duplicate_detector::{
+        compute_data_hash, compute_file_hash, find_duplicates, find_result_duplicates, DuplicateInfo,
     };
-    pub use crate::scanner::file_scanner::{collect_pdf_files, ValidationResult};
-    pub use crate::scanner::duplicate_detector::{compute_file_hash, find_duplicates, DuplicateInfo};
     pub use crate::reporting::report_writer::{write_report, write_simple_report};
+    pub use crate::reporting::{
+        write_csv_report, write_html_report, write_json_report, write_junit_report, write_sarif_report, FileAction,
+        JsonlSink, ReportSummary, RunInfo,
+    };
 }
@@ -1,32 +1,89 @@
 use anyhow::{Context, Result};
-use clap::Parser;
-use indicatif::{ParallelProgressIterator, ProgressBar, ProgressStyle};
+use clap::{Parser, Subcommand, ValueEnum};
+use indicatif::{ProgressBar, ProgressStyle};
 use rayon::prelude::*;
-use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs::{self, File};
-use std::path::PathBuf;
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::{Arc, Mutex};
-use std::time::SystemTime;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
 
 // Import from our modularized library
+use pdf_validator_rs::cache::CheckpointMismatch;
+use pdf_validator_rs::isolation::run_worker;
+use pdf_validator_rs::scanner::archive::is_member_path;
+use pdf_validator_rs::scanner::content_sniffer::{describe_content, read_prefix};
+use pdf_validator_rs::scanner::scan_options::{parse_size, parse_time};
 use pdf_validator_rs::prelude::*;
 
-/// Checkpoint data for resuming validation
-#[derive(Serialize, Deserialize)]
-struct Checkpoint {
-    completed_paths: Vec<PathBuf>,
-    timestamp: SystemTime,
-    total_files: usize,
-}
+// Per-thread allocation counts back the --memory-limit budget
+#[global_allocator]
+static ALLOCATOR: TrackingAllocator = TrackingAllocator;
+
+/// Files to validate, in discovery order
+type Discovered = Box<dyn Iterator<Item = pdf_validator_rs::core::error::Result<DiscoveredFile>> + Send>;
 
 #[derive(Parser)]
 #[command(name = "pdf_validator_rs")]
 #[command(about = "High-performance PDF validator written in Rust", long_about = None)]
+#[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
 struct Cli {
-    /// Target directory to scan for PDF files
-    directory: PathBuf,
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Directories to scan and PDF files to validate, or `-` to validate one PDF read from stdin
+    #[arg(value_name = "PATH", required_unless_present = "files_from", conflicts_with = "files_from")]
+    paths: Vec<PathBuf>,
+
+    /// Also discover PDFs by content (a %PDF- header in the first 1024 bytes), not only by extension
+    #[arg(long)]
+    sniff_content: bool,
+
+    /// Report files whose extension and content disagree as mislabeled
+    #[arg(long)]
+    report_mislabeled: bool,
+
+    /// Follow symlinks to files and directories (cycles are skipped, each target is validated once)
+    #[arg(long)]
+    follow_symlinks: bool,
+
+    /// Validate PDFs inside .zip, .tar, .tar.gz and .tgz archives (reported as archive.zip!/member.pdf)
+    #[arg(long)]
+    scan_archives: bool,
+
+    /// Only scan files matching this glob (repeatable)
+    #[arg(long, value_name = "GLOB")]
+    include: Vec<String>,
+
+    /// Skip files and directories matching this glob (repeatable)
+    #[arg(long, value_name = "GLOB")]
+    exclude: Vec<String>,
+
+    /// Maximum directory depth to scan (1 = only the target directory)
+    #[arg(long, value_name = "N")]
+    max_depth: Option<usize>,
+
+    /// Skip files smaller than this size (e.g. 10K, 2M)
+    #[arg(long, value_name = "SIZE", value_parser = parse_size)]
+    min_size: Option<u64>,
+
+    /// Skip files larger than this size (e.g. 500M, 1G)
+    #[arg(long, value_name = "SIZE", value_parser = parse_size)]
+    max_size: Option<u64>,
+
+    /// Only scan files modified after this age or date (e.g. 7d, 12h, 2024-01-31)
+    #[arg(long, value_name = "WHEN", value_parser = parse_time)]
+    newer_than: Option<SystemTime>,
+
+    /// Only scan files modified before this age or date (e.g. 30d, 2023-12-31)
+    #[arg(long, value_name = "WHEN", value_parser = parse_time)]
+    older_than: Option<SystemTime>,
+
+    /// Validate the paths listed in this file (newline- or NUL-separated; `-` reads stdin)
+    #[arg(long, value_name = "FILE")]
+    files_from: Option<PathBuf>,
 
     /// Scan directories recursively
     #[arg(short, long)]
@@ -36,15 +93,35 @@ struct Cli {
     #[arg(short, long)]
     workers: Option<usize>,
 
-    /// Output report filename
-    #[arg(short, long, default_value = "validation_report_rust.txt")]
-    output: PathBuf,
+    /// Output report filename [default: validation_report_rust.txt, or the extension of --format]
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+
+    /// Also stream one JSON object per file to this path (`-` for stdout) as files finish
+    #[arg(long, value_name = "PATH")]
+    jsonl: Option<PathBuf>,
+
+    /// Report format
+    #[arg(long, value_enum, default_value_t = ReportFormat::Text)]
+    format: ReportFormat,
 
     /// Resume from a previous checkpoint file
     #[arg(long)]
     resume_from: Option<PathBuf>,
 
-    /// Delete invalid/corrupted PDF files
+    /// Resume even if the checkpoint was written for other roots or options
+    #[arg(long, requires = "resume_from")]
+    force_resume: bool,
+
+    /// Write a checkpoint after this many files
+    #[arg(long, value_name = "N", default_value_t = 1000)]
+    checkpoint_every: usize,
+
+    /// Write a checkpoint at least this often (seconds)
+    #[arg(long, value_name = "SECONDS", default_value_t = 60)]
+    checkpoint_interval: u64,
+
+    /// Delete PDF files found corrupt (never ones that timed out, crashed or were skipped)
     #[arg(long)]
     delete_invalid: bool,
 
@@ -71,12 +148,120 @@ struct Cli {
     /// Use lenient parsing mode (accept more PDFs with minor issues)
     #[arg(long)]
     lenient: bool,
+
+    /// Deep validation: resolve every page, its resources, contents and media box
+    #[arg(long)]
+    deep: bool,
+
+    /// Decode every stream and verify its /Length (catches truncated downloads)
+    #[arg(long)]
+    verify_streams: bool,
+
+    /// Record a file as timed out after this many seconds (without --isolate, at most 32 stuck files keep running)
+    #[arg(long, value_name = "SECONDS")]
+    timeout_per_file: Option<u64>,
+
+    /// Memory budget per file in megabytes; implies --isolate, capping each worker's address space
+    #[arg(long, value_name = "MB")]
+    memory_limit: Option<u64>,
+
+    /// Validate each file in a separate worker process so crashes only affect that file
+    #[arg(long)]
+    isolate: bool,
+
+    /// Re-validate every file instead of reusing cached results
+    #[arg(long)]
+    no_cache: bool,
+
+    /// Result cache location (default: the report path with a .cache suffix)
+    #[arg(long, value_name = "FILE", conflicts_with = "no_cache")]
+    cache_path: Option<PathBuf>,
+
+    /// Only reuse cached results when the file's SHA-256 also matches (reads every file)
+    #[arg(long, conflicts_with = "no_cache")]
+    cache_verify_hash: bool,
+}
+
+/// Format of the report written at the end of a run
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum ReportFormat {
+    /// Human-readable summary and listings
+    Text,
+    /// One JSON document with run metadata, summary, files and duplicate groups
+    Json,
+    /// One comma-separated row per file, for spreadsheets
+    Csv,
+    /// One tab-separated row per file
+    Tsv,
+    /// A self-contained page with charts, a filterable table of invalid files and directory rollups
+    Html,
+    /// JUnit XML with one test case per file, for CI test reports
+    Junit,
+    /// SARIF 2.1.0 with one result per invalid file, for code-scanning tools
+    Sarif,
+}
+
+impl ReportFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            ReportFormat::Text => "txt",
+            ReportFormat::Json => "json",
+            ReportFormat::Csv => "csv",
+            ReportFormat::Tsv => "tsv",
+            ReportFormat::Html => "html",
+            ReportFormat::Junit => "xml",
+            ReportFormat::Sarif => "sarif",
+        }
+    }
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Serve validation requests from the parent process over stdin/stdout
+    #[command(hide = true)]
+    Worker,
+}
+
+/// Set when `--jsonl -` streams results to stdout; messages then go to stderr
+static STDOUT_RESERVED: AtomicBool = AtomicBool::new(false);
+
+/// `println!` unless stdout carries machine-readable output
+macro_rules! say {
+    ($($arg:tt)*) => {
+        if STDOUT_RESERVED.load(Ordering::Relaxed) {
+            eprintln!($($arg)*)
+        } else {
+            println!($($arg)*)
+        }
+    };
 }
 
 fn main() -> Result<()> {
-    let cli = Cli::parse();
+    let mut cli = Cli::parse();
+    // Only a worker process can be held to a memory limit
+    cli.isolate |= cli.memory_limit.is_some();
+
+    if let Some(Command::Worker) = cli.command {
+        // Ctrl-C reaches the whole process group; the parent decides when workers stop
+        ctrlc::set_handler(|| {}).context("Error setting Ctrl-C handler")?;
+        let stdin = std::io::stdin();
+        let stdout = std::io::stdout();
+        run_worker(stdin.lock(), stdout.lock())?;
+        return Ok(());
+    }
+
+    if cli.paths.iter().any(|path| path == Path::new("-")) {
+        if cli.paths.len() > 1 {
+            anyhow::bail!("`-` (stdin) cannot be combined with other paths");
+        }
+        return validate_stdin(&cli);
+    }
+
+    if cli.jsonl.as_deref() == Some(Path::new("-")) {
+        STDOUT_RESERVED.store(true, Ordering::Relaxed);
+    }
 
-    // Set up graceful shutdown handler
+    // Set up graceful shutdown handler (SIGINT, SIGTERM and SIGHUP)
     let shutdown_requested = Arc::new(AtomicBool::new(false));
     let shutdown_flag = shutdown_requested.clone();
     
@@ -84,7 +269,7 @@ fn main() -> Result<()> {
         eprintln!("\n⚠️  Shutdown requested. Finishing current files...");
         shutdown_flag.store(true, Ordering::SeqCst);
     })
-    .context("Error setting Ctrl-C handler")?;
+    .context("Error setting signal handler")?;
 
     // Set up rayon thread pool
     if let Some(workers) = cli.workers {
@@ -94,21 +279,58 @@ fn main() -> Result<()> {
             .context("Failed to build thread pool")?;
     }
 
+    let started = SystemTime::now();
+    let report_output = report_path(&cli);
     let num_threads = rayon::current_num_threads();
-    println!("PDF Validator (Rust Edition)");
-    println!("Using {} worker thread(s)", num_threads);
-    println!();
+    say!("PDF Validator (Rust Edition)");
+    say!("Using {} worker thread(s)", num_threads);
+    say!();
+
+    // A file list is read once, up front: it may be stdin, and its
+    // contents identify the run in checkpoints
+    let file_list = match cli.files_from {
+        Some(ref list) => Some(read_files_from(list)?),
+        None => None,
+    };
 
     // Load checkpoint if resuming
-    let mut completed_files: HashSet<PathBuf> = HashSet::new();
+    let roots = run_roots(&cli, file_list.as_deref());
+    let config = run_config(&cli, build_validator(&cli).options());
+    let mut resumed = Checkpoint::default();
     if let Some(ref checkpoint_path) = cli.resume_from {
         if checkpoint_path.exists() {
-            match load_checkpoint(checkpoint_path) {
-                Ok(checkpoint) => {
-                    completed_files = checkpoint.completed_paths.into_iter().collect();
-                    println!("📂 Resuming from checkpoint: {}", checkpoint_path.display());
-                    println!("   Already validated {} files", completed_files.len());
-                    println!();
+            match Checkpoint::load(checkpoint_path) {
+                Ok(mut checkpoint) => {
+                    match checkpoint.check_compatible(&roots, &config) {
+                        Ok(()) => {}
+                        Err(CheckpointMismatch::Unverifiable) => {
+                            eprintln!("⚠️  Warning: Cannot verify checkpoint {}: it predates root and option tracking", checkpoint_path.display());
+                            eprintln!("   Make sure it was written for the same directories and options.\n");
+                        }
+                        Err(mismatch) if cli.force_resume => {
+                            eprintln!("⚠️  WARNING: {} (continuing because of --force-resume)", mismatch);
+                            eprintln!("   Files recorded in the checkpoint will not be validated again.\n");
+                        }
+                        Err(mismatch) => {
+                            anyhow::bail!(
+                                "Refusing to resume from {}: {}. Start a fresh run, or pass --force-resume to use it anyway",
+                                checkpoint_path.display(),
+                                mismatch
+                            );
+                        }
+                    }
+
+                    let changed = checkpoint.drop_changed();
+                    say!("📂 Resuming from checkpoint: {}", checkpoint_path.display());
+                    say!("   Already validated {} files", checkpoint.completed_paths.len());
+                    if !changed.is_empty() {
+                        say!("   {} file(s) changed since the checkpoint and will be validated again", changed.len());
+                    }
+                    if checkpoint.results.is_empty() && !checkpoint.completed_paths.is_empty() {
+                        eprintln!("⚠️  Warning: Checkpoint has no stored results; the report will only cover files validated in this run");
+                    }
+                    say!();
+                    resumed = checkpoint;
                 }
                 Err(e) => {
                     eprintln!("⚠️  Warning: Failed to load checkpoint: {}", e);
@@ -121,41 +343,41 @@ fn main() -> Result<()> {
         }
     }
 
-    // Collect PDF files
-    let all_pdf_files = collect_pdf_files(&cli.directory, cli.recursive)?;
-    
-    // Filter out already-completed files
-    let pdf_files: Vec<PathBuf> = all_pdf_files
-        .into_iter()
-        .filter(|path| !completed_files.contains(path))
-        .collect();
-    
-    let total_files = pdf_files.len();
-    let already_completed = completed_files.len();
+    resumed.roots = roots.clone();
+    resumed.config = config;
+    let completed_files: HashSet<PathBuf> = resumed.completed_paths.iter().cloned().collect();
+    let previous_results = resumed.results.clone();
 
-    if total_files == 0 && already_completed > 0 {
-        println!("✅ All {} PDF files already validated!", already_completed);
-        return Ok(());
-    } else if total_files == 0 {
-        println!("No PDF files found in the specified directory.");
-        return Ok(());
-    }
+    // Discover PDF files as a stream so validation starts before the scan ends
+    // A file list has no roots to prune under: only its own files were seen
+    let prune_roots: Vec<PathBuf> = match file_list {
+        Some(_) => Vec::new(),
+        None => cli.paths.iter().chain(&roots).cloned().collect(),
+    };
+    let scan_archives = cli.scan_archives;
+    let discovered: Discovered = match file_list {
+        Some(files) => Box::new(
+            files
+                .into_iter()
+                .map(move |path| Ok(discovered_path(path, scan_archives))),
+        ),
+        None => scan_roots(&cli.paths, &scan_options(&cli))?,
+    };
 
+    let already_completed = completed_files.len();
     if already_completed > 0 {
-        println!("Found {} new PDF file(s) to validate ({} already completed)\n", 
-            total_files, already_completed);
-    } else {
-        println!("Found {} PDF file(s) to validate\n", total_files);
+        say!("Skipping {} file(s) already completed", already_completed);
     }
+    say!("Scanning and validating PDF files...\n");
 
-    // Set up progress bar (skip in batch mode)
+    // Set up progress bar (skip in batch mode); its length grows as files are discovered
     let progress = if cli.batch {
         ProgressBar::hidden()
     } else {
-        let pb = ProgressBar::new(total_files as u64);
+        let pb = ProgressBar::new(0);
         pb.set_style(
             ProgressStyle::default_bar()
-                .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} ({percent}%) {msg}")
+                .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos} validated / {len} discovered {msg}")
                 .unwrap()
                 .progress_chars("#>-"),
         );
@@ -164,169 +386,355 @@ fn main() -> Result<()> {
 
     // Validate files in parallel
     let check_rendering = !cli.no_render_check;
-    let use_lenient = cli.lenient;
+    let discovery = scan_options(&cli).discovery;
+    let validator = build_validator(&cli);
+    let strictness = validator.options().strictness;
+    let cache = open_cache(&cli, validator.options());
+    let pool = if cli.isolate {
+        let command = WorkerCommand::current_exe().context("Failed to locate worker executable")?;
+        Some(WorkerPool::new(command, validator.options().clone(), num_threads))
+    } else {
+        None
+    };
     let shutdown_check = shutdown_requested.clone();
     
     // Partial results file for incremental saving
-    let partial_output = PathBuf::from(format!("{}.partial", cli.output.display()));
-    let checkpoint_output = PathBuf::from(format!("{}.checkpoint", cli.output.display()));
+    let partial_output = PathBuf::from(format!("{}.partial", report_output.display()));
+    let checkpoint_output = PathBuf::from(format!("{}.checkpoint", report_output.display()));
     
-    // Thread-safe accumulator for completed paths
-    let completed_paths: Arc<Mutex<Vec<PathBuf>>> = Arc::new(Mutex::new(Vec::new()));
-    let completed_clone = completed_paths.clone();
+    // Periodically persists completed files and their results
+    let checkpointer = Checkpointer::new(
+        &checkpoint_output,
+        resumed,
+        cli.checkpoint_every,
+        Duration::from_secs(cli.checkpoint_interval),
+    );
+    let checkpoint_failed = AtomicBool::new(false);
+
+    // A resumed run adds to the stream of the interrupted one
+    let jsonl = match cli.jsonl {
+        Some(ref target) => Some(
+            JsonlSink::create(target, cli.resume_from.is_some())
+                .with_context(|| format!("Failed to open JSON Lines output {}", target.display()))?,
+        ),
+        None => None,
+    };
+    let jsonl_failed = AtomicBool::new(false);
+    let processed = AtomicUsize::new(0);
+    let discovered_count = AtomicUsize::new(0);
+    let cache_hits = AtomicUsize::new(0);
+
+    // Stop discovering once shutdown is requested; skip already-completed files
+    // and files reached again through an overlapping root
+    let mut seen_paths: HashSet<PathBuf> = HashSet::new();
+    let pdf_files = discovered
+        .take_while(|_| !shutdown_check.load(Ordering::SeqCst))
+        .filter_map(|item| match item {
+            Ok(found) if completed_files.contains(&found.path) || !seen_paths.insert(found.path.clone()) => None,
+            Ok(found) => {
+                discovered_count.fetch_add(1, Ordering::SeqCst);
+                progress.inc_length(1);
+                Some(found)
+            }
+            Err(e) => {
+                progress.suspend(|| eprintln!("⚠️  Warning: {}", e));
+                None
+            }
+        });
+
+    // Record a finished file for the checkpoint, the JSON Lines stream and the progress bar
+    let mark_done = |path: &Path, results: &[ValidationResult]| {
+        if let Some(ref jsonl) = jsonl {
+            if let Err(e) = jsonl.record(results) {
+                if !jsonl_failed.swap(true, Ordering::SeqCst) {
+                    progress.suspend(|| eprintln!("⚠️  Warning: Failed to write JSON Lines output: {}", e));
+                }
+            }
+        }
+        let total = already_completed + discovered_count.load(Ordering::SeqCst);
+        if let Err(e) = checkpointer.record(path, results, total) {
+            // Warn once; later checkpoints are still attempted
+            if !checkpoint_failed.swap(true, Ordering::SeqCst) {
+                progress.suspend(|| eprintln!("⚠️  Warning: Failed to save checkpoint: {}", e));
+            }
+        }
+        processed.fetch_add(1, Ordering::SeqCst);
+        progress.inc(1);
+    };
 
     let results: Vec<ValidationResult> = pdf_files
-        .par_iter()
-        .progress_with(progress.clone())
-        .filter_map(|path| {
+        .par_bridge()
+        .flat_map_iter(|found| {
             // Check if shutdown was requested
             if shutdown_check.load(Ordering::SeqCst) {
-                return None; // Stop processing new files
+                return Vec::new(); // Stop processing new files
             }
             
-            // Choose validation method based on flags
-            let is_valid = if use_lenient {
-                // Lenient mode - accept more PDFs
-                validate_pdf_lenient(path)
-            } else if check_rendering {
-                // Strict mode with rendering check
-                let basic_valid = validate_pdf(path, cli.verbose);
-                if basic_valid && cfg!(feature = "rendering") {
-                    // Also check if pages can be rendered
-                    // validate_pdf_rendering(path, 5) // Check first 5 pages
-                    validate_pdf_lenient(path) // Fallback when rendering not available
-                } else {
-                    basic_valid
+            let DiscoveredFile { path, mislabel, dangling_target, root, archive } = found;
+            let path = &path;
+
+            // Unchanged files reuse the results of an earlier run
+            if let Some(cached) = cache.as_ref().filter(|_| dangling_target.is_none()).and_then(|c| c.get(path)) {
+                cache_hits.fetch_add(1, Ordering::Relaxed);
+                let cached: Vec<_> = cached.into_iter().map(|result| result.with_root(root.clone())).collect();
+                mark_done(path, &cached);
+                return cached;
+            }
+
+            // Archive members are validated in memory, one result per member,
+            // and in the workers under --isolate
+            if let Some(kind) = archive {
+                let members = match pool {
+                    Some(ref pool) => validate_archive_with(validator.options(), path, kind, discovery, |member, data| {
+                        pool.validate_bytes(member, data)
+                    }),
+                    None => validate_archive(&validator, path, kind, discovery),
+                };
+                let members: Vec<_> = members
+                    .into_iter()
+                    .map(|result| result.with_root(root.clone()))
+                    .collect();
+                if cli.verbose {
+                    for member in members.iter().filter(|m| m.outcome.verdict != Verdict::Valid) {
+                        progress.suspend(|| eprintln!("{:?}: {}", member.path, member.outcome));
+                    }
+                }
+                if let Some(ref cache) = cache {
+                    cache.insert(path, &members);
                 }
+                mark_done(path, &members);
+                return members;
+            }
+
+            // Choose validation method based on flags
+            let validation_started = Instant::now();
+            let outcome = match (&dangling_target, mislabel, &pool) {
+                (Some(target), _, _) => dangling_link_outcome(target),
+                (None, Some(Mislabel::NotPdf), _) => not_pdf_outcome(path).unwrap_or_else(|| validator.validate(path)),
+                (None, _, Some(pool)) => pool.validate(path),
+                (None, _, None) => validator.validate(path),
+            };
+            let outcome = if pool.is_none() && mislabel != Some(Mislabel::NotPdf) && dangling_target.is_none() && check_rendering && strictness == Strictness::Strict
+                && outcome.is_valid() && cfg!(feature = "rendering")
+            {
+                // Also check if pages can be rendered
+                // validate_pdf_rendering(path, 5) // Check first 5 pages
+                validator.validate_with_strictness(path, Strictness::Lenient) // Fallback when rendering not available
             } else {
-                // Normal strict mode
-                validate_pdf(path, cli.verbose)
+                outcome
             };
-            
-            // Track completed path for checkpoint
-            if let Ok(mut paths) = completed_clone.lock() {
-                paths.push(path.clone());
+
+            if cli.verbose && outcome.verdict != Verdict::Valid {
+                progress.suspend(|| eprintln!("{:?}: {}", path, outcome));
             }
 
-            Some(ValidationResult {
-                path: path.clone(),
-                is_valid,
-            })
+            let metadata = fs::metadata(path).ok();
+            let result = ValidationResult::new(path.clone(), outcome)
+                .with_mislabel(mislabel)
+                .with_root(root)
+                .with_size(metadata.as_ref().map(|metadata| metadata.len()))
+                .with_modified(metadata.and_then(|metadata| metadata.modified().ok()))
+                .with_duration(validation_started.elapsed());
+            if let Some(ref cache) = cache {
+                cache.insert(path, std::slice::from_ref(&result));
+            }
+            mark_done(path, std::slice::from_ref(&result));
+            vec![result]
         })
         .collect();
 
+    // Keep the cache even for interrupted runs; it only holds finished files.
+    // Only a complete run knows which files are gone, so only it prunes, and
+    // only under its own roots: other runs may share the cache.
+    if let Some(ref cache) = cache {
+        if !shutdown_requested.load(Ordering::SeqCst) {
+            for path in &completed_files {
+                cache.keep(path);
+            }
+            cache.prune(&prune_roots);
+        }
+        let hits = cache_hits.load(Ordering::Relaxed);
+        if hits > 0 {
+            say!("Reused cached results for {} unchanged file(s)", hits);
+        }
+        if let Err(e) = cache.save() {
+            eprintln!("⚠️  Warning: Failed to save result cache: {}", e);
+        }
+    }
+
     // Display progress summary
-    let processed_count = results.len();
+    let processed_count = processed.load(Ordering::SeqCst);
+    let total_files = discovered_count.load(Ordering::SeqCst);
     let was_interrupted = shutdown_requested.load(Ordering::SeqCst);
+
+    if total_files == 0 && !was_interrupted {
+        progress.finish_and_clear();
+        if let Some(ref jsonl) = jsonl {
+            let run = run_info(&cli, started, was_interrupted, roots, validator.options());
+            let summary = ReportSummary::new(&previous_results, None);
+            jsonl.finish(&run, &summary).context("Failed to write JSON Lines summary")?;
+        }
+        if already_completed > 0 {
+            say!("✅ All {} PDF files already validated!", already_completed);
+        } else {
+            say!("No PDF files found to validate.");
+        }
+        return Ok(());
+    }
     
     // Save checkpoint if interrupted
     if was_interrupted {
-        if let Ok(paths) = completed_paths.lock() {
-            // Add previously completed files from checkpoint
-            let mut all_completed: Vec<PathBuf> = completed_files.into_iter().collect();
-            all_completed.extend(paths.clone());
-            
-            if let Err(e) = save_checkpoint(&checkpoint_output, all_completed, total_files + already_completed) {
-                eprintln!("⚠️  Warning: Failed to save checkpoint: {}", e);
-            } else {
-                eprintln!("💾 Checkpoint saved to: {}", checkpoint_output.display());
-                eprintln!("💡 Resume later with: --resume-from {}", checkpoint_output.display());
-            }
+        if let Err(e) = checkpointer.save() {
+            eprintln!("⚠️  Warning: Failed to save checkpoint: {}", e);
+        } else {
+            eprintln!("💾 Checkpoint saved to: {}", checkpointer.path().display());
+            eprintln!("💡 Resume later with: --resume-from {}", checkpointer.path().display());
         }
     }
+
+    // Files validated before the resumed checkpoint belong in the report too.
+    // Results arrive in whatever order the workers finish; sort them so
+    // duplicate handling, deletion and reports are the same on every run.
+    let mut results: Vec<ValidationResult> = previous_results.into_iter().chain(results).collect();
+    results.sort_by(|a, b| a.path.cmp(&b.path));
     
     if !cli.batch {
         if was_interrupted {
             progress.finish_and_clear();
             eprintln!("\n⏹️  Graceful shutdown complete");
-            eprintln!("📊 Processed {}/{} files ({:.1}%)", 
+            eprintln!("📊 Processed {}/{} discovered files ({:.1}%)", 
                 processed_count, 
                 total_files,
-                (processed_count as f64 / total_files as f64) * 100.0
+                (processed_count as f64 / total_files.max(1) as f64) * 100.0
             );
         } else {
             progress.finish_with_message("Validation complete!");
         }
-        println!();
+        say!();
     }
 
+    // What was done to files after validation, for the CSV and HTML reports
+    let mut actions: HashMap<PathBuf, FileAction> = HashMap::new();
+
     // Detect duplicates if requested
     let duplicates = if cli.detect_duplicates || cli.delete_duplicates {
-        println!("Detecting duplicate files...");
-        let valid_paths: Vec<_> = results
-            .iter()
-            .filter(|r| r.is_valid)
-            .map(|r| r.path.clone())
-            .collect();
-
-        match find_duplicates(&valid_paths) {
-            Ok(dups) => {
-                println!("Found {} groups of duplicate files\n", dups.len());
-
-                // Delete duplicates if requested (keep first file in each group)
-                if cli.delete_duplicates && !dups.is_empty() {
-                    let mut total_deleted = 0;
-                    for dup_group in &dups {
-                        // Skip first file (keep it), delete the rest
-                        for path in dup_group.paths.iter().skip(1) {
-                            match fs::remove_file(path) {
-                                Ok(_) => {
-                                    total_deleted += 1;
-                                    if cli.verbose {
-                                        println!("Deleted duplicate: {}", path.display());
-                                    }
-                                }
-                                Err(e) => {
-                                    eprintln!("Error deleting duplicate {:?}: {}", path, e);
-                                }
+        say!("Detecting duplicate files...");
+        // Archive members are hashed during validation; files on disk are hashed here
+        let dups = find_result_duplicates(results.iter().filter(|r| r.is_valid()));
+        say!("Found {} groups of duplicate files\n", dups.len());
+
+        // Delete duplicates if requested (keep the first file on disk in each group)
+        if cli.delete_duplicates && !dups.is_empty() {
+            let mut total_deleted = 0;
+            for dup_group in &dups {
+                // A group made only of archive members has no copy to keep
+                let Some(kept) = dup_group.keep() else {
+                    continue;
+                };
+                actions.insert(kept.to_path_buf(), FileAction::KeptDuplicate);
+                // Archive members can't be deleted
+                for path in dup_group.paths.iter().filter(|path| *path != kept && !is_member_path(path)) {
+                    match fs::remove_file(path) {
+                        Ok(_) => {
+                            actions.insert(path.clone(), FileAction::DeletedDuplicate);
+                            total_deleted += 1;
+                            if cli.verbose {
+                                say!("Deleted duplicate: {}", path.display());
                             }
                         }
+                        Err(e) => {
+                            actions.insert(path.clone(), FileAction::DeleteFailed);
+                            eprintln!("Error deleting duplicate {:?}: {}", path, e);
+                        }
                     }
-                    println!("Deleted {} duplicate file(s)\n", total_deleted);
                 }
-
-                Some(dups)
-            }
-            Err(e) => {
-                eprintln!("Error detecting duplicates: {}", e);
-                None
             }
+            say!("Deleted {} duplicate file(s)\n", total_deleted);
         }
+
+        Some(dups)
     } else {
         None
     };
 
     // Separate valid and invalid files
-    let valid_count = results.iter().filter(|r| r.is_valid).count();
+    let valid_count = results.iter().filter(|r| r.is_valid()).count();
     let invalid_count = results.len() - valid_count;
 
+    // Only files found definitely corrupt are deleted. Timeouts, crashes,
+    // limits and skipped files are reported, as are archives and members.
     let invalid_files: Vec<_> = results
         .iter()
-        .filter(|r| !r.is_valid)
+        .filter(|r| {
+            r.outcome.is_corrupt()
+                && !is_member_path(&r.path)
+                && !(cli.scan_archives && ArchiveKind::from_path(&r.path).is_some())
+        })
         .map(|r| &r.path)
         .collect();
 
     // Print summary
-    println!("==================================================");
-    println!("VALIDATION COMPLETE");
-    println!("==================================================");
-    println!("Valid PDF files: {}", valid_count);
-    println!("Invalid PDF files: {}", invalid_count);
-    println!();
+    say!("==================================================");
+    say!("VALIDATION COMPLETE");
+    say!("==================================================");
+    say!("Valid PDF files: {}", valid_count);
+    say!("Invalid PDF files: {}", invalid_count);
+    let timed_out_count = results
+        .iter()
+        .filter(|r| r.outcome.verdict == Verdict::TimedOut)
+        .count();
+    if timed_out_count > 0 {
+        say!("Timed out: {}", timed_out_count);
+    }
+    let crashed_count = results
+        .iter()
+        .filter(|r| r.outcome.verdict == Verdict::Crashed)
+        .count();
+    if crashed_count > 0 {
+        say!("Crashed workers: {}", crashed_count);
+    }
+    let panicked_count = results
+        .iter()
+        .filter(|r| r.outcome.verdict == Verdict::Panicked)
+        .count();
+    if panicked_count > 0 {
+        say!("Validator panics: {}", panicked_count);
+    }
+    let mislabeled_count = results.iter().filter(|r| r.mislabel.is_some()).count();
+    if mislabeled_count > 0 {
+        say!("Mislabeled files: {}", mislabeled_count);
+    }
+    let over_limit_count = results
+        .iter()
+        .filter(|r| r.outcome.verdict == Verdict::ResourceLimitExceeded)
+        .count();
+    if over_limit_count > 0 {
+        say!("Over memory limit: {}", over_limit_count);
+    }
+    let dangling_count = results
+        .iter()
+        .filter(|r| r.outcome.category == Some(FailureCategory::DanglingLink))
+        .count();
+    if dangling_count > 0 {
+        say!("Dangling symlinks: {}", dangling_count);
+    }
+    say!();
 
     // Delete invalid files if requested
     if cli.delete_invalid && !invalid_files.is_empty() {
-        println!("Deleting {} invalid file(s)...", invalid_files.len());
+        say!("Deleting {} invalid file(s)...", invalid_files.len());
         let mut deleted_count = 0;
         for path in &invalid_files {
             if let Err(e) = fs::remove_file(path) {
+                actions.insert(path.to_path_buf(), FileAction::DeleteFailed);
                 eprintln!("Error deleting {:?}: {}", path, e);
             } else {
+                actions.insert(path.to_path_buf(), FileAction::DeletedInvalid);
                 deleted_count += 1;
             }
         }
-        println!("Deleted {} invalid file(s)", deleted_count);
-        println!();
+        say!("Deleted {} invalid file(s)", deleted_count);
+        say!();
     }
 
     // Write report
@@ -335,19 +743,28 @@ fn main() -> Result<()> {
         &partial_output
     } else {
         // Use final output if completed
-        &cli.output
+        &report_output
     };
-    
-    write_report(
-        output_file,
-        &results,
-        duplicates.as_deref(),
-    )?;
+
+    let run = run_info(&cli, started, was_interrupted, roots, validator.options());
+    match cli.format {
+        ReportFormat::Text => write_report(output_file, &results, duplicates.as_deref())?,
+        ReportFormat::Json => write_json_report(output_file, &run, &results, duplicates.as_deref())?,
+        ReportFormat::Csv => write_csv_report(output_file, &results, duplicates.as_deref(), &actions, ',')?,
+        ReportFormat::Tsv => write_csv_report(output_file, &results, duplicates.as_deref(), &actions, '\t')?,
+        ReportFormat::Html => write_html_report(output_file, &run, &results, duplicates.as_deref(), &actions)?,
+        ReportFormat::Junit => write_junit_report(output_file, &run, &results)?,
+        ReportFormat::Sarif => write_sarif_report(output_file, &run, &results)?,
+    }
+    if let Some(ref jsonl) = jsonl {
+        let summary = ReportSummary::new(&results, duplicates.as_deref());
+        jsonl.finish(&run, &summary).context("Failed to write JSON Lines summary")?;
+    }
     
     if was_interrupted {
-        println!("Partial results saved to: {:?}", partial_output);
+        say!("Partial results saved to: {:?}", partial_output);
     } else {
-        println!("Detailed report saved to: {:?}", cli.output);
+        say!("Detailed report saved to: {:?}", report_output);
         // Clean up checkpoint if we completed successfully
         let _ = fs::remove_file(&checkpoint_output);
     }
@@ -355,25 +772,240 @@ fn main() -> Result<()> {
     Ok(())
 }
 
-/// Load checkpoint from file
-fn load_checkpoint(path: &PathBuf) -> Result<Checkpoint> {
-    let file = File::open(path)
-        .context("Failed to open checkpoint file")?;
-    let checkpoint: Checkpoint = serde_json::from_reader(file)
-        .context("Failed to parse checkpoint file")?;
-    Ok(checkpoint)
+/// Build the validator described by the command line
+fn build_validator(cli: &Cli) -> Validator {
+    let strictness = if cli.lenient {
+        Strictness::Lenient
+    } else {
+        Strictness::Strict
+    };
+    let level = if cli.deep {
+        ValidationLevel::Deep
+    } else {
+        ValidationLevel::Standard
+    };
+    Validator::builder()
+        .strictness(strictness)
+        .level(level)
+        .verify_streams(cli.verify_streams)
+        .timeout(cli.timeout_per_file.map(Duration::from_secs))
+        .memory_limit(cli.memory_limit.map(|mb| mb * 1024 * 1024))
+        .build()
+}
+
+/// Stream candidates from every root: directories are scanned, files are taken as given
+fn scan_roots(roots: &[PathBuf], options: &ScanOptions) -> Result<Discovered> {
+    let mut streams: Vec<Discovered> = Vec::with_capacity(roots.len());
+    for root in roots {
+        if root.is_dir() {
+            let stream = scan_parallel(root, options)
+                .with_context(|| format!("Failed to scan {}", root.display()))?;
+            streams.push(Box::new(stream));
+        } else if root.exists() {
+            let found = DiscoveredFile {
+                root: Some(root.clone()),
+                ..discovered_path(root.clone(), options.scan_archives)
+            };
+            streams.push(Box::new(std::iter::once(Ok(found))));
+        } else {
+            anyhow::bail!("Path not found: {}", root.display());
+        }
+    }
+    Ok(Box::new(streams.into_iter().flatten()))
+}
+
+/// Build the directory scan options described by the command line
+fn scan_options(cli: &Cli) -> ScanOptions {
+    ScanOptions {
+        recursive: cli.recursive,
+        max_depth: cli.max_depth,
+        include: cli.include.clone(),
+        exclude: cli.exclude.clone(),
+        min_size: cli.min_size,
+        max_size: cli.max_size,
+        newer_than: cli.newer_than,
+        older_than: cli.older_than,
+        discovery: if cli.sniff_content {
+            Discovery::Content
+        } else {
+            Discovery::Extension
+        },
+        report_mislabeled: cli.report_mislabeled,
+        follow_symlinks: cli.follow_symlinks,
+        scan_archives: cli.scan_archives,
+        ..ScanOptions::default()
+    }
+}
+
+/// A file named explicitly, flagged as an archive when archives are scanned
+fn discovered_path(path: PathBuf, scan_archives: bool) -> DiscoveredFile {
+    DiscoveredFile {
+        archive: ArchiveKind::from_path(&path).filter(|_| scan_archives),
+        ..DiscoveredFile::new(path)
+    }
+}
+
+/// Report file: `--output`, or the default name for the report format
+fn report_path(cli: &Cli) -> PathBuf {
+    cli.output
+        .clone()
+        .unwrap_or_else(|| PathBuf::from(format!("validation_report_rust.{}", cli.format.extension())))
+}
+
+/// Metadata of a finished run for machine-readable reports
+fn run_info(
+    cli: &Cli,
+    started: SystemTime,
+    interrupted: bool,
+    roots: Vec<PathBuf>,
+    options: &ValidationOptions,
+) -> RunInfo {
+    RunInfo {
+        finished: SystemTime::now(),
+        threads: rayon::current_num_threads(),
+        interrupted,
+        roots,
+        scan: scan_options(cli),
+        validation: options.clone(),
+        settings: run_settings(cli),
+        ..RunInfo::new(started)
+    }
+}
+
+/// Command-line settings recorded in machine-readable reports
+fn run_settings(cli: &Cli) -> BTreeMap<String, serde_json::Value> {
+    let settings: [(&str, serde_json::Value); 8] = [
+        ("render_check", (!cli.no_render_check && cfg!(feature = "rendering")).into()),
+        ("isolate", cli.isolate.into()),
+        ("detect_duplicates", (cli.detect_duplicates || cli.delete_duplicates).into()),
+        ("delete_duplicates", cli.delete_duplicates.into()),
+        ("delete_invalid", cli.delete_invalid.into()),
+        ("result_cache", (!cli.no_cache).into()),
+        ("resumed_from", cli.resume_from.as_ref().map(|p| p.display().to_string()).into()),
+        ("jsonl", cli.jsonl.as_ref().map(|p| p.display().to_string()).into()),
+    ];
+    settings.into_iter().map(|(name, value)| (name.to_string(), value)).collect()
+}
+
+/// Settings outside the validator options that still change results
+fn result_settings(cli: &Cli) -> String {
+    format!(
+        "render={} sniff={} mislabeled={} archives={}",
+        !cli.no_render_check && cfg!(feature = "rendering"),
+        cli.sniff_content,
+        cli.report_mislabeled,
+        cli.scan_archives,
+    )
+}
+
+/// Scan roots recorded in checkpoints, canonicalized so `./docs` and `docs` match
+///
+/// A run over `--files-from` is identified by a hash of the listed paths
+/// rather than the list's own path, which says nothing about what it held.
+fn run_roots(cli: &Cli, file_list: Option<&[PathBuf]>) -> Vec<PathBuf> {
+    if let Some(files) = file_list {
+        let mut listed = Vec::new();
+        for file in files {
+            listed.extend_from_slice(file.as_os_str().as_encoded_bytes());
+            listed.push(0);
+        }
+        return vec![PathBuf::from(format!("--files-from sha256:{}", compute_data_hash(&listed)))];
+    }
+    cli.paths
+        .iter()
+        .map(|root| fs::canonicalize(root).unwrap_or_else(|_| root.clone()))
+        .collect()
+}
+
+/// Fingerprint of everything that decides which files are scanned and how they are judged
+///
+/// Age filters are left out: `--newer-than 7d` resolves to a different
+/// instant on every run.
+fn run_config(cli: &Cli, options: &ValidationOptions) -> String {
+    let scan = ScanOptions {
+        newer_than: None,
+        older_than: None,
+        ..scan_options(cli)
+    };
+    cache_fingerprint(options, &format!("{:?} {}", scan, result_settings(cli)))
+}
+
+/// Open the result cache unless disabled
+///
+/// A cache that cannot be read is reported and replaced by an empty one.
+fn open_cache(cli: &Cli, options: &ValidationOptions) -> Option<ResultCache> {
+    if cli.no_cache {
+        return None;
+    }
+    let path = cli
+        .cache_path
+        .clone()
+        .unwrap_or_else(|| PathBuf::from(format!("{}.cache", report_path(cli).display())));
+    let fingerprint = cache_fingerprint(options, &result_settings(cli));
+
+    match ResultCache::open(&path, fingerprint.clone(), cli.cache_verify_hash) {
+        Ok(cache) => Some(cache),
+        Err(e) => {
+            eprintln!("⚠️  Warning: Ignoring unreadable result cache {}: {}", path.display(), e);
+            Some(ResultCache::new(&path, fingerprint, cli.cache_verify_hash))
+        }
+    }
 }
 
-/// Save checkpoint to file
-fn save_checkpoint(path: &PathBuf, completed_paths: Vec<PathBuf>, total_files: usize) -> Result<()> {
-    let checkpoint = Checkpoint {
-        completed_paths,
-        timestamp: SystemTime::now(),
-        total_files,
+/// Validate a single PDF streamed on stdin
+///
+/// Prints the outcome and exits with status 1 if the document is not valid.
+fn validate_stdin(cli: &Cli) -> Result<()> {
+    let mut data = Vec::new();
+    std::io::stdin()
+        .lock()
+        .read_to_end(&mut data)
+        .context("Failed to read PDF from stdin")?;
+
+    let validator = build_validator(cli);
+    let outcome = if cli.isolate {
+        let command = WorkerCommand::current_exe().context("Failed to locate worker executable")?;
+        WorkerPool::new(command, validator.options().clone(), 1).validate_bytes(Path::new("<stdin>"), &data)
+    } else {
+        validator.validate_bytes(&data)
     };
-    let file = File::create(path)
-        .context("Failed to create checkpoint file")?;
-    serde_json::to_writer_pretty(file, &checkpoint)
-        .context("Failed to write checkpoint file")?;
+    println!("<stdin>: {}", outcome);
+    if !outcome.is_valid() {
+        std::process::exit(1);
+    }
     Ok(())
 }
+
+/// Outcome for a `.pdf` file whose content is not a PDF at all
+///
+/// Returns `None` if the file cannot be read, leaving the validator to
+/// report the error.
+fn not_pdf_outcome(path: &Path) -> Option<ValidationOutcome> {
+    let prefix = read_prefix(path).ok()?;
+    Some(ValidationOutcome::failure(
+        FailureCategory::Mislabeled,
+        ValidationStage::QuickCheck,
+        format!("Named .pdf but not a PDF: {}", describe_content(&prefix)),
+    ))
+}
+
+/// Outcome for a symlink whose target is missing
+fn dangling_link_outcome(target: &Path) -> ValidationOutcome {
+    ValidationOutcome::failure(
+        FailureCategory::DanglingLink,
+        ValidationStage::QuickCheck,
+        format!("Symlink target does not exist: {}", target.display()),
+    )
+}
+
+/// Read the list of files to validate from a file, or stdin for `-`
+fn read_files_from(source: &Path) -> Result<Vec<PathBuf>> {
+    let files = if source == Path::new("-") {
+        read_file_list(std::io::stdin().lock()).context("Failed to read file list from stdin")?
+    } else {
+        let file = File::open(source)
+            .with_context(|| format!("Failed to open file list {}", source.display()))?;
+        read_file_list(file).context("Failed to read file list")?
+    };
+    Ok(files)
+}
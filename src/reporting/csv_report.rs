@@ -0,0 +1,193 @@
+//! CSV and TSV export for spreadsheets
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use super::timestamp::format_rfc3339;
+use crate::core::error::Result;
+use crate::scanner::duplicate_detector::DuplicateInfo;
+use crate::scanner::file_scanner::ValidationResult;
+
+/// Column names, in order; new columns are only ever appended
+pub const CSV_COLUMNS: [&str; 12] = [
+    "path",
+    "directory",
+    "size_bytes",
+    "mtime",
+    "verdict",
+    "category",
+    "message",
+    "pdf_version",
+    "pages",
+    "sha256",
+    "duplicate_group_id",
+    "action_taken",
+];
+
+/// What the run did to a file after validating it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FileAction {
+    /// Deleted by `--delete-invalid`
+    DeletedInvalid,
+    /// Deleted by `--delete-duplicates`
+    DeletedDuplicate,
+    /// Kept as the first copy of a duplicate group
+    KeptDuplicate,
+    /// Deletion was attempted and failed
+    DeleteFailed,
+}
+
+impl FileAction {
+    /// Stable machine-readable name
+    pub fn as_str(self) -> &'static str {
+        match self {
+            FileAction::DeletedInvalid => "deleted_invalid",
+            FileAction::DeletedDuplicate => "deleted_duplicate",
+            FileAction::KeptDuplicate => "kept_duplicate",
+            FileAction::DeleteFailed => "delete_failed",
+        }
+    }
+}
+
+/// Write one row per result, with a header row
+///
+/// Fields are separated by `delimiter` (`,` for CSV, `\t` for TSV) and
+/// quoted when they contain the delimiter, a quote or a line break, as in
+/// RFC 4180. Empty fields mean "not applicable" or "not known".
+///
+/// # Arguments
+/// * `output_path` - Path to output file
+/// * `results` - Validation results to write
+/// * `duplicates` - Optional duplicate file information
+/// * `actions` - What was done to files after validation, by path
+/// * `delimiter` - Field separator
+pub fn write_csv_report(
+    output_path: &Path,
+    results: &[ValidationResult],
+    duplicates: Option<&[DuplicateInfo]>,
+    actions: &HashMap<PathBuf, FileAction>,
+    delimiter: char,
+) -> Result<()> {
+    // Group ids match the numbering of the text report
+    let groups: HashMap<&Path, (usize, &str)> = duplicates
+        .unwrap_or_default()
+        .iter()
+        .enumerate()
+        .flat_map(|(idx, group)| {
+            group
+                .paths
+                .iter()
+                .map(move |path| (path.as_path(), (idx + 1, group.hash.as_str())))
+        })
+        .collect();
+
+    let mut writer = BufWriter::new(File::create(output_path)?);
+    write_row(&mut writer, CSV_COLUMNS.iter().map(|column| column.to_string()), delimiter)?;
+
+    for result in results {
+        let group = groups.get(result.path.as_path());
+        let outcome = &result.outcome;
+        let row = [
+            result.path.display().to_string(),
+            result
+                .path
+                .parent()
+                .map(|dir| dir.display().to_string())
+                .unwrap_or_default(),
+            optional(result.size),
+            result.modified.map(format_rfc3339).unwrap_or_default(),
+            outcome.verdict.to_string(),
+            optional(outcome.category),
+            outcome.message.clone().unwrap_or_default(),
+            outcome.pdf_version.clone().unwrap_or_default(),
+            optional(outcome.pages),
+            result
+                .sha256
+                .as_deref()
+                .or(group.map(|(_, hash)| *hash))
+                .unwrap_or_default()
+                .to_string(),
+            optional(group.map(|(id, _)| *id)),
+            actions
+                .get(&result.path)
+                .map(|action| action.as_str().to_string())
+                .unwrap_or_default(),
+        ];
+        write_row(&mut writer, row.into_iter(), delimiter)?;
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+fn optional<T: ToString>(value: Option<T>) -> String {
+    value.map(|v| v.to_string()).unwrap_or_default()
+}
+
+fn write_row(out: &mut impl Write, fields: impl Iterator<Item = String>, delimiter: char) -> Result<()> {
+    let line: Vec<String> = fields.map(|field| quote_field(&field, delimiter)).collect();
+    out.write_all(line.join(&delimiter.to_string()).as_bytes())?;
+    out.write_all(b"\r\n")?;
+    Ok(())
+}
+
+/// Quote a field if it contains the delimiter, a quote or a line break
+fn quote_field(field: &str, delimiter: char) -> String {
+    if field.contains([delimiter, '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::outcome::{FailureCategory, Strategy, ValidationOutcome, ValidationStage};
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn test_quote_field() {
+        assert_eq!(quote_field("plain.pdf", ','), "plain.pdf");
+        assert_eq!(quote_field("a,b.pdf", ','), "\"a,b.pdf\"");
+        assert_eq!(quote_field("a,b.pdf", '\t'), "a,b.pdf");
+        assert_eq!(quote_field("say \"hi\".pdf", ','), "\"say \"\"hi\"\".pdf\"");
+        assert_eq!(quote_field("two\nlines.pdf", '\t'), "\"two\nlines.pdf\"");
+    }
+
+    #[test]
+    fn test_write_csv_report() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let mut valid = ValidationOutcome::valid(Strategy::PdfRs);
+        valid.pages = Some(2);
+        valid.pdf_version = Some("1.7".to_string());
+        let results = vec![
+            ValidationResult::new(PathBuf::from("/docs/a.pdf"), valid.clone()).with_size(Some(1234)),
+            ValidationResult::new(PathBuf::from("/docs/copy, of a.pdf"), valid),
+            ValidationResult::new(
+                PathBuf::from("/docs/bad.pdf"),
+                ValidationOutcome::failure(FailureCategory::MissingEof, ValidationStage::QuickCheck, "Missing %%EOF marker"),
+            ),
+        ];
+        let duplicates = vec![DuplicateInfo {
+            hash: "abc".to_string(),
+            paths: vec![PathBuf::from("/docs/a.pdf"), PathBuf::from("/docs/copy, of a.pdf")],
+        }];
+        let actions = HashMap::from([
+            (PathBuf::from("/docs/a.pdf"), FileAction::KeptDuplicate),
+            (PathBuf::from("/docs/copy, of a.pdf"), FileAction::DeletedDuplicate),
+        ]);
+
+        write_csv_report(temp_file.path(), &results, Some(&duplicates), &actions, ',').unwrap();
+
+        let content = std::fs::read_to_string(temp_file.path()).unwrap();
+        let lines: Vec<_> = content.split("\r\n").collect();
+        assert_eq!(lines[0], CSV_COLUMNS.join(","));
+        assert_eq!(lines[1], "/docs/a.pdf,/docs,1234,,valid,,,1.7,2,abc,1,kept_duplicate");
+        assert_eq!(lines[2], "\"/docs/copy, of a.pdf\",/docs,,,valid,,,1.7,2,abc,1,deleted_duplicate");
+        assert_eq!(lines[3], "/docs/bad.pdf,/docs,,,invalid,missing_eof,Missing %%EOF marker,,,,,");
+        assert_eq!(lines[4], "");
+    }
+}
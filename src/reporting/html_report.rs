@@ -0,0 +1,447 @@
+//! Self-contained HTML report
+//!
+//! The page embeds its styles, script and SVG charts, so it can be emailed
+//! or archived with the run and opened without network access.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write as _;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use super::csv_report::FileAction;
+use super::json_report::ReportSummary;
+use super::run_info::RunInfo;
+use super::timestamp::format_rfc3339;
+use crate::core::error::Result;
+use crate::scanner::duplicate_detector::DuplicateInfo;
+use crate::scanner::file_scanner::ValidationResult;
+
+/// Upper bounds (exclusive) and labels of the size histogram buckets
+const SIZE_BUCKETS: [(u64, &str); 6] = [
+    (10 << 10, "< 10 KB"),
+    (100 << 10, "10–100 KB"),
+    (1 << 20, "100 KB–1 MB"),
+    (10 << 20, "1–10 MB"),
+    (100 << 20, "10–100 MB"),
+    (u64::MAX, "≥ 100 MB"),
+];
+
+const STYLE: &str = r#"
+body { font-family: system-ui, sans-serif; margin: 2rem; color: #222; }
+h1 { margin-bottom: 0.2rem; }
+.meta { color: #666; margin-top: 0; }
+.cards { display: flex; gap: 1rem; flex-wrap: wrap; margin: 1rem 0; }
+.card { border: 1px solid #ddd; border-radius: 6px; padding: 0.6rem 1rem; min-width: 8rem; }
+.card b { display: block; font-size: 1.6rem; }
+.charts { display: flex; gap: 2rem; flex-wrap: wrap; }
+.chart h3 { margin-bottom: 0.3rem; }
+svg text { font-size: 12px; fill: #333; }
+table { border-collapse: collapse; width: 100%; margin: 0.5rem 0 1.5rem; }
+th, td { border-bottom: 1px solid #eee; padding: 0.3rem 0.5rem; text-align: left; vertical-align: top; }
+th { background: #f6f6f6; }
+th.sortable { cursor: pointer; user-select: none; }
+th.sortable::after { content: " ⇅"; color: #aaa; }
+td.num, th.num { text-align: right; }
+.path { font-family: ui-monospace, monospace; word-break: break-all; }
+.filters { display: flex; gap: 0.5rem; margin: 0.5rem 0; }
+.filters input { flex: 1; padding: 0.3rem; }
+details { border: 1px solid #eee; border-radius: 4px; margin: 0.3rem 0; padding: 0.3rem 0.6rem; }
+summary { cursor: pointer; }
+.kept { color: #1a7f37; font-weight: bold; }
+.deleted { color: #b42318; text-decoration: line-through; }
+.bar-valid { fill: #2da44e; }
+.bar-warn { fill: #d4a72c; }
+.bar-bad { fill: #cf222e; }
+.bar-other { fill: #8c959f; }
+.bar-size { fill: #0969da; }
+"#;
+
+const SCRIPT: &str = r#"
+(function () {
+  var table = document.getElementById("invalid");
+  if (!table) return;
+  var body = table.tBodies[0];
+  var text = document.getElementById("filter-text");
+  var category = document.getElementById("filter-category");
+  function applyFilter() {
+    var needle = text.value.toLowerCase();
+    var wanted = category.value;
+    Array.prototype.forEach.call(body.rows, function (row) {
+      var matches = row.textContent.toLowerCase().indexOf(needle) !== -1
+        && (wanted === "" || row.getAttribute("data-category") === wanted);
+      row.style.display = matches ? "" : "none";
+    });
+  }
+  text.addEventListener("input", applyFilter);
+  category.addEventListener("change", applyFilter);
+  Array.prototype.forEach.call(table.tHead.rows[0].cells, function (th, index) {
+    var ascending = true;
+    th.addEventListener("click", function () {
+      var numeric = th.classList.contains("num");
+      var rows = Array.prototype.slice.call(body.rows);
+      rows.sort(function (a, b) {
+        var x = a.cells[index].getAttribute("data-sort") || a.cells[index].textContent;
+        var y = b.cells[index].getAttribute("data-sort") || b.cells[index].textContent;
+        var order = numeric ? (parseFloat(x) || 0) - (parseFloat(y) || 0) : x.localeCompare(y);
+        return ascending ? order : -order;
+      });
+      ascending = !ascending;
+      rows.forEach(function (row) { body.appendChild(row); });
+    });
+  });
+})();
+"#;
+
+/// Counts and bytes for one directory
+#[derive(Default)]
+struct DirectoryRollup {
+    total: usize,
+    valid: usize,
+    invalid: usize,
+    bytes: u64,
+}
+
+/// Write the results as a single static HTML page
+///
+/// # Arguments
+/// * `output_path` - Path to output file
+/// * `run` - Metadata about the run
+/// * `results` - Validation results to write
+/// * `duplicates` - Optional duplicate file information
+/// * `actions` - What was done to files after validation, by path
+pub fn write_html_report(
+    output_path: &Path,
+    run: &RunInfo,
+    results: &[ValidationResult],
+    duplicates: Option<&[DuplicateInfo]>,
+    actions: &HashMap<PathBuf, FileAction>,
+) -> Result<()> {
+    let summary = ReportSummary::new(results, duplicates);
+    let mut html = String::new();
+
+    html.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str("<title>PDF Validation Report</title>\n");
+    let _ = writeln!(html, "<style>{}</style>\n</head>\n<body>", STYLE);
+    html.push_str("<h1>PDF Validation Report</h1>\n");
+    let _ = writeln!(
+        html,
+        "<p class=\"meta\">pdf_validator_rs {} &middot; started {} &middot; finished {} &middot; {} thread(s){}</p>",
+        escape(&run.version),
+        format_rfc3339(run.started),
+        format_rfc3339(run.finished),
+        run.threads,
+        if run.interrupted { " &middot; <b>interrupted</b>" } else { "" }
+    );
+    if !run.roots.is_empty() {
+        let roots: Vec<_> = run.roots.iter().map(|root| escape(&root.display().to_string())).collect();
+        let _ = writeln!(html, "<p class=\"meta\">Roots: <span class=\"path\">{}</span></p>", roots.join(", "));
+    }
+
+    write_summary_cards(&mut html, &summary);
+    write_charts(&mut html, results, &summary);
+    write_invalid_table(&mut html, results);
+    if let Some(groups) = duplicates {
+        write_duplicate_groups(&mut html, groups, actions);
+    }
+    write_directory_rollups(&mut html, results);
+
+    let _ = writeln!(html, "<script>{}</script>\n</body>\n</html>", SCRIPT);
+
+    let mut writer = BufWriter::new(File::create(output_path)?);
+    writer.write_all(html.as_bytes())?;
+    writer.flush()?;
+    Ok(())
+}
+
+fn write_summary_cards(html: &mut String, summary: &ReportSummary) {
+    html.push_str("<div class=\"cards\">\n");
+    let mut cards = vec![
+        ("Files", summary.total_files),
+        ("Valid", summary.valid),
+        ("Invalid", summary.invalid),
+    ];
+    if let Some(groups) = summary.duplicate_groups {
+        cards.push(("Duplicate groups", groups));
+    }
+    for (label, value) in cards {
+        let _ = writeln!(html, "<div class=\"card\"><b>{}</b>{}</div>", value, label);
+    }
+    html.push_str("</div>\n");
+}
+
+fn write_charts(html: &mut String, results: &[ValidationResult], summary: &ReportSummary) {
+    html.push_str("<h2>Overview</h2>\n<div class=\"charts\">\n");
+
+    let verdicts: Vec<_> = summary
+        .by_verdict
+        .iter()
+        .map(|(verdict, count)| {
+            let class = match verdict.as_str() {
+                "valid" => "bar-valid",
+                "valid_with_warnings" => "bar-warn",
+                "invalid" => "bar-bad",
+                _ => "bar-other",
+            };
+            (verdict.to_string(), *count, class)
+        })
+        .collect();
+    write_bar_chart(html, "Verdicts", &verdicts);
+
+    let categories: Vec<_> = summary
+        .by_category
+        .iter()
+        .map(|(category, count)| (category.to_string(), *count, "bar-bad"))
+        .collect();
+    write_bar_chart(html, "Failure categories", &categories);
+
+    let mut sizes = [0usize; SIZE_BUCKETS.len()];
+    for size in results.iter().filter_map(|r| r.size) {
+        let bucket = SIZE_BUCKETS.iter().position(|(limit, _)| size < *limit).unwrap_or(SIZE_BUCKETS.len() - 1);
+        sizes[bucket] += 1;
+    }
+    let sizes: Vec<_> = SIZE_BUCKETS
+        .iter()
+        .zip(sizes)
+        .map(|((_, label), count)| (label.to_string(), count, "bar-size"))
+        .collect();
+    write_bar_chart(html, "File sizes", &sizes);
+
+    html.push_str("</div>\n");
+}
+
+/// Horizontal bar chart as inline SVG
+fn write_bar_chart(html: &mut String, title: &str, bars: &[(String, usize, &str)]) {
+    const LABEL_WIDTH: usize = 170;
+    const BAR_WIDTH: usize = 220;
+    const ROW_HEIGHT: usize = 22;
+
+    let _ = writeln!(html, "<div class=\"chart\"><h3>{}</h3>", escape(title));
+    if bars.is_empty() {
+        html.push_str("<p>None</p></div>\n");
+        return;
+    }
+    let max = bars.iter().map(|(_, count, _)| *count).max().unwrap_or(0).max(1);
+    let _ = writeln!(
+        html,
+        "<svg width=\"{}\" height=\"{}\" role=\"img\" aria-label=\"{}\">",
+        LABEL_WIDTH + BAR_WIDTH + 60,
+        bars.len() * ROW_HEIGHT,
+        escape(title)
+    );
+    for (row, (label, count, class)) in bars.iter().enumerate() {
+        let y = row * ROW_HEIGHT;
+        let width = count * BAR_WIDTH / max;
+        let _ = writeln!(
+            html,
+            "<text x=\"{}\" y=\"{}\" text-anchor=\"end\">{}</text>\
+             <rect class=\"{}\" x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\"></rect>\
+             <text x=\"{}\" y=\"{}\">{}</text>",
+            LABEL_WIDTH - 6,
+            y + 15,
+            escape(label),
+            class,
+            LABEL_WIDTH,
+            y + 3,
+            width,
+            ROW_HEIGHT - 6,
+            LABEL_WIDTH + width + 6,
+            y + 15,
+            count
+        );
+    }
+    html.push_str("</svg></div>\n");
+}
+
+fn write_invalid_table(html: &mut String, results: &[ValidationResult]) {
+    let invalid: Vec<_> = results.iter().filter(|r| !r.is_valid()).collect();
+    let _ = writeln!(html, "<h2>Invalid Files ({})</h2>", invalid.len());
+    if invalid.is_empty() {
+        html.push_str("<p>No invalid files.</p>\n");
+        return;
+    }
+
+    let mut categories: Vec<_> = invalid.iter().filter_map(|r| r.outcome.category).collect();
+    categories.sort();
+    categories.dedup();
+    html.push_str("<div class=\"filters\"><input id=\"filter-text\" type=\"search\" placeholder=\"Filter by path or message\">");
+    html.push_str("<select id=\"filter-category\"><option value=\"\">All categories</option>");
+    for category in categories {
+        let _ = write!(html, "<option value=\"{0}\">{0}</option>", category);
+    }
+    html.push_str("</select></div>\n");
+
+    html.push_str("<table id=\"invalid\">\n<thead><tr>");
+    html.push_str("<th class=\"sortable\">Path</th><th class=\"sortable\">Verdict</th>");
+    html.push_str("<th class=\"sortable\">Category</th><th class=\"sortable\">Message</th>");
+    html.push_str("<th class=\"sortable num\">Size</th></tr></thead>\n<tbody>\n");
+    for result in invalid {
+        let category = result.outcome.category.map(|c| c.to_string()).unwrap_or_default();
+        let _ = writeln!(
+            html,
+            "<tr data-category=\"{}\"><td class=\"path\">{}</td><td>{}</td><td>{}</td><td>{}</td>\
+             <td class=\"num\" data-sort=\"{}\">{}</td></tr>",
+            category,
+            escape(&result.path.display().to_string()),
+            result.outcome.verdict,
+            category,
+            escape(result.outcome.message.as_deref().unwrap_or("")),
+            result.size.unwrap_or(0),
+            result.size.map(format_size).unwrap_or_default()
+        );
+    }
+    html.push_str("</tbody>\n</table>\n");
+}
+
+fn write_duplicate_groups(html: &mut String, groups: &[DuplicateInfo], actions: &HashMap<PathBuf, FileAction>) {
+    let _ = writeln!(html, "<h2>Duplicate Groups ({})</h2>", groups.len());
+    if groups.is_empty() {
+        html.push_str("<p>No duplicates found.</p>\n");
+        return;
+    }
+    for (idx, group) in groups.iter().enumerate() {
+        let _ = writeln!(
+            html,
+            "<details><summary>Group {} &middot; {} copies &middot; <code>{}</code></summary><ul>",
+            idx + 1,
+            group.paths.len(),
+            escape(&group.hash)
+        );
+        let keep = group.keep();
+        for path in &group.paths {
+            let (class, note) = match actions.get(path) {
+                Some(FileAction::DeletedDuplicate) => ("deleted", "deleted"),
+                Some(FileAction::DeleteFailed) => ("", "delete failed"),
+                _ if Some(path.as_path()) == keep => ("kept", "kept"),
+                _ => ("", "copy"),
+            };
+            let _ = writeln!(
+                html,
+                "<li><span class=\"path {}\">{}</span> ({})</li>",
+                class,
+                escape(&path.display().to_string()),
+                note
+            );
+        }
+        html.push_str("</ul></details>\n");
+    }
+}
+
+fn write_directory_rollups(html: &mut String, results: &[ValidationResult]) {
+    let mut directories: BTreeMap<String, DirectoryRollup> = BTreeMap::new();
+    for result in results {
+        let directory = result
+            .path
+            .parent()
+            .map(|dir| dir.display().to_string())
+            .unwrap_or_default();
+        let rollup = directories.entry(directory).or_default();
+        rollup.total += 1;
+        if result.is_valid() {
+            rollup.valid += 1;
+        } else {
+            rollup.invalid += 1;
+        }
+        rollup.bytes += result.size.unwrap_or(0);
+    }
+
+    // Directories with the most invalid files first
+    let mut directories: Vec<_> = directories.into_iter().collect();
+    directories.sort_by(|(a_dir, a), (b_dir, b)| b.invalid.cmp(&a.invalid).then_with(|| a_dir.cmp(b_dir)));
+
+    let _ = writeln!(html, "<h2>Directories ({})</h2>", directories.len());
+    html.push_str("<table>\n<thead><tr><th>Directory</th><th class=\"num\">Files</th><th class=\"num\">Valid</th>");
+    html.push_str("<th class=\"num\">Invalid</th><th class=\"num\">Size</th></tr></thead>\n<tbody>\n");
+    for (directory, rollup) in directories {
+        let _ = writeln!(
+            html,
+            "<tr><td class=\"path\">{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td>\
+             <td class=\"num\">{}</td><td class=\"num\">{}</td></tr>",
+            escape(&directory),
+            rollup.total,
+            rollup.valid,
+            rollup.invalid,
+            format_size(rollup.bytes)
+        );
+    }
+    html.push_str("</tbody>\n</table>\n");
+}
+
+/// Human-readable size, e.g. `1.5 MB`
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+/// Escape text for use in HTML content and attribute values
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::outcome::{FailureCategory, Strategy, ValidationOutcome, ValidationStage};
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn test_escape_and_format_size() {
+        assert_eq!(escape("<a href=\"x\">&'</a>"), "&lt;a href=&quot;x&quot;&gt;&amp;&#39;&lt;/a&gt;");
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(1536), "1.5 KB");
+    }
+
+    #[test]
+    fn test_write_html_report() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let results = vec![
+            ValidationResult::new(PathBuf::from("/docs/a.pdf"), ValidationOutcome::valid(Strategy::PdfRs))
+                .with_size(Some(2048)),
+            ValidationResult::new(PathBuf::from("/docs/b.pdf"), ValidationOutcome::valid(Strategy::PdfRs)),
+            ValidationResult::new(
+                PathBuf::from("/scans/<bad>.pdf"),
+                ValidationOutcome::failure(FailureCategory::MissingEof, ValidationStage::QuickCheck, "Missing %%EOF marker"),
+            ),
+        ];
+        let duplicates = vec![DuplicateInfo {
+            hash: "abc".to_string(),
+            paths: vec![PathBuf::from("/docs/a.pdf"), PathBuf::from("/docs/b.pdf")],
+        }];
+        let actions = HashMap::from([(PathBuf::from("/docs/b.pdf"), FileAction::DeletedDuplicate)]);
+        let run = RunInfo::new(std::time::SystemTime::now());
+
+        write_html_report(temp_file.path(), &run, &results, Some(&duplicates), &actions).unwrap();
+
+        let html = std::fs::read_to_string(temp_file.path()).unwrap();
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(!html.contains("src=\"http") && !html.contains("href=\"http"));
+        assert!(html.contains("<h2>Invalid Files (1)</h2>"));
+        assert!(html.contains("/scans/&lt;bad&gt;.pdf"));
+        assert!(html.contains("<option value=\"missing_eof\">missing_eof</option>"));
+        assert!(html.contains("<span class=\"path kept\">/docs/a.pdf</span> (kept)"));
+        assert!(html.contains("<span class=\"path deleted\">/docs/b.pdf</span> (deleted)"));
+        assert!(html.contains("<h2>Directories (2)</h2>"));
+        assert!(html.contains("File sizes"));
+    }
+}
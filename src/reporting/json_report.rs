@@ -0,0 +1,219 @@
+//! JSON report for dashboards and scripts
+
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use super::run_info::RunInfo;
+use crate::core::error::Result;
+use crate::core::outcome::{FailureCategory, Strategy, ValidationStage, Verdict};
+use crate::scanner::content_sniffer::Mislabel;
+use crate::scanner::duplicate_detector::DuplicateInfo;
+use crate::scanner::file_scanner::ValidationResult;
+
+/// Bumped whenever a field is renamed or removed
+pub const JSON_REPORT_VERSION: u32 = 1;
+
+/// Counts over all results of a run
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ReportSummary {
+    pub total_files: usize,
+    pub valid: usize,
+    /// Everything not valid, including skipped and timed-out files
+    pub invalid: usize,
+    pub by_verdict: BTreeMap<Verdict, usize>,
+    pub by_category: BTreeMap<FailureCategory, usize>,
+    /// Present when duplicates were detected
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duplicate_groups: Option<usize>,
+    /// Copies beyond the first in each duplicate group
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub redundant_copies: Option<usize>,
+}
+
+impl ReportSummary {
+    pub fn new<'a>(results: impl IntoIterator<Item = &'a ValidationResult>, duplicates: Option<&[DuplicateInfo]>) -> Self {
+        let mut summary = Self::default();
+        for result in results {
+            summary.add(result);
+        }
+        if let Some(groups) = duplicates {
+            summary.duplicate_groups = Some(groups.len());
+            summary.redundant_copies = Some(groups.iter().map(|g| g.paths.len().saturating_sub(1)).sum());
+        }
+        summary
+    }
+
+    /// Count one more result
+    pub fn add(&mut self, result: &ValidationResult) {
+        self.total_files += 1;
+        if result.is_valid() {
+            self.valid += 1;
+        } else {
+            self.invalid += 1;
+        }
+        *self.by_verdict.entry(result.outcome.verdict).or_default() += 1;
+        if let Some(category) = result.outcome.category {
+            *self.by_category.entry(category).or_default() += 1;
+        }
+    }
+}
+
+/// One file in a machine-readable report
+#[derive(Debug, Clone, Serialize)]
+pub struct FileRecord<'a> {
+    pub path: &'a Path,
+    pub root: Option<&'a Path>,
+    pub size: Option<u64>,
+    pub verdict: Verdict,
+    pub category: Option<FailureCategory>,
+    pub stage: Option<ValidationStage>,
+    pub message: Option<&'a str>,
+    pub strategy: Option<Strategy>,
+    pub mislabel: Option<Mislabel>,
+    pub duration_ms: Option<f64>,
+    pub pages: Option<u32>,
+    /// SHA-256 of the content, when it was computed during the run
+    pub sha256: Option<&'a str>,
+}
+
+impl<'a> FileRecord<'a> {
+    /// Record for `result`, with `sha256` used when the result carries no hash itself
+    pub fn new(result: &'a ValidationResult, sha256: Option<&'a str>) -> Self {
+        Self {
+            path: &result.path,
+            root: result.root.as_deref(),
+            size: result.size,
+            verdict: result.outcome.verdict,
+            category: result.outcome.category,
+            stage: result.outcome.stage,
+            message: result.outcome.message.as_deref(),
+            strategy: result.outcome.strategy,
+            mislabel: result.mislabel,
+            duration_ms: result.duration.map(|d| d.as_secs_f64() * 1000.0),
+            pages: result.outcome.pages,
+            sha256: result.sha256.as_deref().or(sha256),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct DuplicateGroup<'a> {
+    sha256: &'a str,
+    /// The copy kept by `--delete-duplicates`; `null` if every copy is an archive member
+    keep: Option<&'a Path>,
+    paths: &'a [std::path::PathBuf],
+}
+
+#[derive(Serialize)]
+struct JsonReport<'a> {
+    report_version: u32,
+    run: &'a RunInfo,
+    summary: ReportSummary,
+    files: Vec<FileRecord<'a>>,
+    /// `null` when duplicates were not detected
+    duplicates: Option<Vec<DuplicateGroup<'a>>>,
+}
+
+/// Write the results as a single JSON document
+///
+/// # Arguments
+/// * `output_path` - Path to output file
+/// * `run` - Metadata about the run
+/// * `results` - Validation results to write
+/// * `duplicates` - Optional duplicate file information
+pub fn write_json_report(
+    output_path: &Path,
+    run: &RunInfo,
+    results: &[ValidationResult],
+    duplicates: Option<&[DuplicateInfo]>,
+) -> Result<()> {
+    // Files on disk are only hashed by duplicate detection
+    let hashes: HashMap<&Path, &str> = duplicates
+        .unwrap_or_default()
+        .iter()
+        .flat_map(|group| group.paths.iter().map(move |path| (path.as_path(), group.hash.as_str())))
+        .collect();
+
+    let report = JsonReport {
+        report_version: JSON_REPORT_VERSION,
+        run,
+        summary: ReportSummary::new(results, duplicates),
+        files: results
+            .iter()
+            .map(|result| FileRecord::new(result, hashes.get(result.path.as_path()).copied()))
+            .collect(),
+        duplicates: duplicates.map(|groups| {
+            groups
+                .iter()
+                .filter(|group| !group.paths.is_empty())
+                .map(|group| DuplicateGroup {
+                    sha256: &group.hash,
+                    keep: group.keep(),
+                    paths: &group.paths,
+                })
+                .collect()
+        }),
+    };
+
+    let mut writer = BufWriter::new(File::create(output_path)?);
+    serde_json::to_writer_pretty(&mut writer, &report)?;
+    writeln!(writer)?;
+    writer.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::outcome::ValidationOutcome;
+    use std::path::PathBuf;
+    use std::time::Duration;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn test_write_json_report() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let mut valid = ValidationOutcome::valid(Strategy::PdfRs);
+        valid.pages = Some(3);
+        let results = vec![
+            ValidationResult::new(PathBuf::from("/test/a.pdf"), valid.clone())
+                .with_size(Some(2048))
+                .with_duration(Duration::from_millis(5)),
+            ValidationResult::new(PathBuf::from("/test/b.pdf"), valid),
+            ValidationResult::new(
+                PathBuf::from("/test/bad.pdf"),
+                ValidationOutcome::failure(FailureCategory::MissingEof, ValidationStage::QuickCheck, "Missing %%EOF marker"),
+            ),
+        ];
+        let duplicates = vec![DuplicateInfo {
+            hash: "abc".to_string(),
+            paths: vec![PathBuf::from("/test/a.pdf"), PathBuf::from("/test/b.pdf")],
+        }];
+        let mut run = RunInfo::new(std::time::UNIX_EPOCH);
+        run.interrupted = true;
+
+        write_json_report(temp_file.path(), &run, &results, Some(&duplicates)).unwrap();
+
+        let json: serde_json::Value = serde_json::from_reader(File::open(temp_file.path()).unwrap()).unwrap();
+        assert_eq!(json["report_version"], 1);
+        assert_eq!(json["run"]["interrupted"], true);
+        assert_eq!(json["run"]["started"], "1970-01-01T00:00:00.000Z");
+        assert_eq!(json["summary"]["total_files"], 3);
+        assert_eq!(json["summary"]["valid"], 2);
+        assert_eq!(json["summary"]["by_category"]["missing_eof"], 1);
+        assert_eq!(json["summary"]["redundant_copies"], 1);
+
+        let first = &json["files"][0];
+        assert_eq!(first["verdict"], "valid");
+        assert_eq!(first["size"], 2048);
+        assert_eq!(first["pages"], 3);
+        assert_eq!(first["duration_ms"], 5.0);
+        assert_eq!(first["sha256"], "abc");
+        assert_eq!(json["files"][2]["category"], "missing_eof");
+        assert_eq!(json["files"][2]["message"], "Missing %%EOF marker");
+        assert_eq!(json["duplicates"][0]["keep"], "/test/a.pdf");
+    }
+}
@@ -0,0 +1,152 @@
+//! JSON Lines stream of results, written while a run is in progress
+
+use serde::Serialize;
+use std::fs::OpenOptions;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use super::json_report::{FileRecord, ReportSummary};
+use super::run_info::RunInfo;
+use crate::core::error::Result;
+use crate::scanner::file_scanner::ValidationResult;
+
+/// Longest time a written line may sit in the buffer
+const FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Lines written between flushes at most
+const FLUSH_EVERY_LINES: usize = 256;
+
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Line<'a> {
+    File(FileRecord<'a>),
+    Summary {
+        run: &'a RunInfo,
+        summary: &'a ReportSummary,
+    },
+}
+
+/// Appends one JSON object per validated file to a file or stdout
+///
+/// Each file becomes a `{"type":"file",...}` line with the fields of
+/// [`FileRecord`]; [`finish`](Self::finish) adds a final
+/// `{"type":"summary",...}` line. Output is flushed at least every second,
+/// so the stream can be tailed and survives the process dying mid-run.
+/// Safe to share between validation threads.
+pub struct JsonlSink {
+    state: Mutex<State>,
+}
+
+struct State {
+    out: BufWriter<Box<dyn Write + Send>>,
+    unflushed: usize,
+    last_flush: Instant,
+}
+
+impl JsonlSink {
+    /// Stream to `out`
+    pub fn new(out: Box<dyn Write + Send>) -> Self {
+        Self {
+            state: Mutex::new(State {
+                out: BufWriter::new(out),
+                unflushed: 0,
+                last_flush: Instant::now(),
+            }),
+        }
+    }
+
+    /// Stream to the file at `target`, or to stdout if it is `-`
+    ///
+    /// With `append`, lines are added to an existing file (as when a run is
+    /// resumed); otherwise the file is truncated.
+    pub fn create(target: &Path, append: bool) -> Result<Self> {
+        if target == Path::new("-") {
+            return Ok(Self::new(Box::new(io::stdout())));
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .append(append)
+            .truncate(!append)
+            .open(target)?;
+        Ok(Self::new(Box::new(file)))
+    }
+
+    /// Write a line for each result of one finished file
+    pub fn record(&self, results: &[ValidationResult]) -> Result<()> {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        for result in results {
+            write_line(&mut state.out, &Line::File(FileRecord::new(result, None)))?;
+            state.unflushed += 1;
+        }
+
+        if state.unflushed >= FLUSH_EVERY_LINES || state.last_flush.elapsed() >= FLUSH_INTERVAL {
+            state.unflushed = 0;
+            state.last_flush = Instant::now();
+            state.out.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Write the summary line and flush
+    pub fn finish(&self, run: &RunInfo, summary: &ReportSummary) -> Result<()> {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        write_line(&mut state.out, &Line::Summary { run, summary })?;
+        state.unflushed = 0;
+        state.out.flush()?;
+        Ok(())
+    }
+}
+
+fn write_line(out: &mut impl Write, line: &Line<'_>) -> Result<()> {
+    serde_json::to_writer(&mut *out, line)?;
+    out.write_all(b"\n")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::outcome::{FailureCategory, Strategy, ValidationOutcome, ValidationStage};
+    use std::path::PathBuf;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_jsonl_sink_streams_files_then_summary() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("results.jsonl");
+        let results = vec![
+            ValidationResult::new(PathBuf::from("/test/a.pdf"), ValidationOutcome::valid(Strategy::PdfRs)),
+            ValidationResult::new(
+                PathBuf::from("/test/bad.pdf"),
+                ValidationOutcome::failure(FailureCategory::BadHeader, ValidationStage::QuickCheck, "Invalid PDF header"),
+            ),
+        ];
+
+        let sink = JsonlSink::create(&path, false).unwrap();
+        sink.record(&results[..1]).unwrap();
+        sink.record(&results[1..]).unwrap();
+        sink.finish(&RunInfo::new(std::time::SystemTime::now()), &ReportSummary::new(&results, None))
+            .unwrap();
+
+        let lines: Vec<serde_json::Value> = std::fs::read_to_string(&path)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0]["type"], "file");
+        assert_eq!(lines[0]["path"], "/test/a.pdf");
+        assert_eq!(lines[1]["category"], "bad_header");
+        assert_eq!(lines[2]["type"], "summary");
+        assert_eq!(lines[2]["summary"]["invalid"], 1);
+
+        // Appending keeps earlier lines
+        let sink = JsonlSink::create(&path, true).unwrap();
+        sink.record(&results[..1]).unwrap();
+        drop(sink);
+        assert_eq!(std::fs::read_to_string(&path).unwrap().lines().count(), 4);
+    }
+}
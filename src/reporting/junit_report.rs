@@ -0,0 +1,215 @@
+//! JUnit XML report for CI systems
+
+use std::fmt::Write as _;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::time::Duration;
+
+use super::run_info::RunInfo;
+use super::timestamp::format_rfc3339;
+use crate::core::error::Result;
+use crate::core::outcome::Verdict;
+use crate::scanner::file_scanner::ValidationResult;
+
+/// How a file shows up as a JUnit test case
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CaseStatus {
+    Passed,
+    /// The file is not a usable PDF
+    Failure,
+    /// Validation could not complete (I/O error, timeout, crash, ...)
+    Error,
+    Skipped,
+}
+
+fn case_status(result: &ValidationResult) -> CaseStatus {
+    match result.outcome.verdict {
+        Verdict::Valid | Verdict::ValidWithWarnings => CaseStatus::Passed,
+        Verdict::Invalid => CaseStatus::Failure,
+        Verdict::Skipped => CaseStatus::Skipped,
+        Verdict::Errored
+        | Verdict::TimedOut
+        | Verdict::Crashed
+        | Verdict::ResourceLimitExceeded
+        | Verdict::Panicked => CaseStatus::Error,
+    }
+}
+
+/// Write one JUnit test case per file
+///
+/// Invalid files are failures and files that could not be validated are
+/// errors, each carrying the failure category as `type` and the message.
+/// Skipped files (mislabeled, dangling links, circuit breaker) are skipped
+/// test cases. Test cases are grouped by directory through `classname`.
+///
+/// # Arguments
+/// * `output_path` - Path to output file
+/// * `run` - Metadata about the run
+/// * `results` - Validation results to write
+pub fn write_junit_report(output_path: &Path, run: &RunInfo, results: &[ValidationResult]) -> Result<()> {
+    let count = |status| results.iter().filter(|r| case_status(r) == status).count();
+    let (failures, errors, skipped) = (count(CaseStatus::Failure), count(CaseStatus::Error), count(CaseStatus::Skipped));
+    let elapsed = run.finished.duration_since(run.started).unwrap_or_default();
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = writeln!(
+        xml,
+        "<testsuites name=\"pdf_validator_rs\" tests=\"{}\" failures=\"{}\" errors=\"{}\" skipped=\"{}\" time=\"{}\">",
+        results.len(),
+        failures,
+        errors,
+        skipped,
+        seconds(elapsed)
+    );
+    let _ = writeln!(
+        xml,
+        "  <testsuite name=\"pdf_validator_rs\" tests=\"{}\" failures=\"{}\" errors=\"{}\" skipped=\"{}\" time=\"{}\" timestamp=\"{}\">",
+        results.len(),
+        failures,
+        errors,
+        skipped,
+        seconds(elapsed),
+        format_rfc3339(run.started)
+    );
+    xml.push_str("    <properties>\n");
+    let mut properties = vec![
+        ("version", run.version.clone()),
+        ("interrupted", run.interrupted.to_string()),
+    ];
+    properties.extend(run.roots.iter().map(|root| ("root", root.display().to_string())));
+    for (name, value) in properties {
+        let _ = writeln!(xml, "      <property name=\"{}\" value=\"{}\"/>", name, escape(&value));
+    }
+    xml.push_str("    </properties>\n");
+
+    for result in results {
+        write_test_case(&mut xml, result);
+    }
+
+    xml.push_str("  </testsuite>\n</testsuites>\n");
+
+    let mut writer = BufWriter::new(File::create(output_path)?);
+    writer.write_all(xml.as_bytes())?;
+    writer.flush()?;
+    Ok(())
+}
+
+fn write_test_case(xml: &mut String, result: &ValidationResult) {
+    let classname = result
+        .path
+        .parent()
+        .map(|dir| dir.display().to_string())
+        .unwrap_or_default();
+    let _ = write!(
+        xml,
+        "    <testcase classname=\"{}\" name=\"{}\" time=\"{}\"",
+        escape(&classname),
+        escape(&result.path.display().to_string()),
+        seconds(result.duration.unwrap_or_default())
+    );
+
+    let outcome = &result.outcome;
+    let category = outcome.category.map(|c| c.as_str()).unwrap_or(outcome.verdict.as_str());
+    let message = outcome.message.as_deref().unwrap_or("");
+    match case_status(result) {
+        CaseStatus::Passed if outcome.verdict == Verdict::Valid => xml.push_str("/>\n"),
+        CaseStatus::Passed => {
+            let _ = writeln!(
+                xml,
+                ">\n      <system-out>Accepted with warnings ({}): {}</system-out>\n    </testcase>",
+                category,
+                escape(message)
+            );
+        }
+        CaseStatus::Skipped => {
+            let _ = writeln!(
+                xml,
+                ">\n      <skipped message=\"{}: {}\"/>\n    </testcase>",
+                category,
+                escape(message)
+            );
+        }
+        status => {
+            let element = if status == CaseStatus::Failure { "failure" } else { "error" };
+            let stage = outcome.stage.map(|s| format!(" at stage {}", s)).unwrap_or_default();
+            let _ = writeln!(
+                xml,
+                ">\n      <{0} type=\"{1}\" message=\"{2}\">{3}: {2}{4}</{0}>\n    </testcase>",
+                element,
+                category,
+                escape(message),
+                outcome.verdict,
+                stage
+            );
+        }
+    }
+}
+
+/// Seconds with millisecond precision, as JUnit's `time` attribute expects
+fn seconds(duration: Duration) -> String {
+    format!("{:.3}", duration.as_secs_f64())
+}
+
+/// Escape text for XML content and attribute values
+///
+/// Characters XML 1.0 cannot represent at all are replaced with U+FFFD.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\t' | '\n' | '\r' => escaped.push(c),
+            c if (c as u32) < 0x20 || c == '\u{FFFE}' || c == '\u{FFFF}' => escaped.push('\u{FFFD}'),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::outcome::{FailureCategory, Strategy, ValidationOutcome, ValidationStage};
+    use std::path::PathBuf;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn test_write_junit_report() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let results = vec![
+            ValidationResult::new(PathBuf::from("/docs/a.pdf"), ValidationOutcome::valid(Strategy::PdfRs))
+                .with_duration(Duration::from_millis(12)),
+            ValidationResult::new(
+                PathBuf::from("/docs/b&c.pdf"),
+                ValidationOutcome::failure(FailureCategory::MissingEof, ValidationStage::QuickCheck, "Missing <%%EOF>"),
+            ),
+            ValidationResult::new(PathBuf::from("/docs/slow.pdf"), ValidationOutcome::timed_out(Duration::from_secs(5))),
+            ValidationResult::new(
+                PathBuf::from("/docs/link.pdf"),
+                ValidationOutcome::failure(FailureCategory::DanglingLink, ValidationStage::QuickCheck, "gone"),
+            ),
+        ];
+
+        write_junit_report(temp_file.path(), &RunInfo::new(std::time::SystemTime::now()), &results).unwrap();
+
+        let xml = std::fs::read_to_string(temp_file.path()).unwrap();
+        assert!(xml.contains("tests=\"4\" failures=\"1\" errors=\"1\" skipped=\"1\""));
+        assert!(xml.contains("<testcase classname=\"/docs\" name=\"/docs/a.pdf\" time=\"0.012\"/>"));
+        assert!(xml.contains("name=\"/docs/b&amp;c.pdf\""));
+        assert!(xml.contains(
+            "<failure type=\"missing_eof\" message=\"Missing &lt;%%EOF&gt;\">invalid: Missing &lt;%%EOF&gt; at stage quick_check</failure>"
+        ));
+        assert!(xml.contains("<error type=\"timeout\""));
+        assert!(xml.contains("<skipped message=\"dangling_link: gone\"/>"));
+    }
+
+    #[test]
+    fn test_escape_replaces_invalid_xml_characters() {
+        assert_eq!(escape("a\u{1}b\tc"), "a\u{FFFD}b\tc");
+    }
+}
@@ -1,5 +1,21 @@
 //! Report generation functionality
 
+pub mod csv_report;
+pub mod html_report;
+pub mod json_report;
+pub mod jsonl_sink;
+pub mod junit_report;
 pub mod report_writer;
+pub mod run_info;
+pub mod sarif_report;
+pub mod timestamp;
 
+pub use csv_report::{write_csv_report, FileAction};
+pub use html_report::write_html_report;
+pub use json_report::{write_json_report, FileRecord, ReportSummary};
+pub use jsonl_sink::JsonlSink;
+pub use junit_report::write_junit_report;
 pub use report_writer::write_report;
+pub use run_info::RunInfo;
+pub use sarif_report::write_sarif_report;
+pub use timestamp::format_rfc3339;
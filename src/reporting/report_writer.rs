@@ -1,10 +1,12 @@
 //! Report writing functionality
 
-use anyhow::Result;
+use crate::core::error::Result;
+use std::collections::{BTreeMap, HashMap};
 use std::fs::File;
 use std::io::Write;
 use std::path::Path;
 
+use crate::core::outcome::{FailureCategory, Verdict};
 use crate::scanner::file_scanner::ValidationResult;
 use crate::scanner::duplicate_detector::DuplicateInfo;
 
@@ -32,7 +34,7 @@ pub fn write_report(
     writeln!(file)?;
 
     // Write summary statistics
-    let valid_count = results.iter().filter(|r| r.is_valid).count();
+    let valid_count = results.iter().filter(|r| r.is_valid()).count();
     let invalid_count = results.len() - valid_count;
 
     writeln!(file, "Summary Statistics:")?;
@@ -41,19 +43,113 @@ pub fn write_report(
     writeln!(file, "  Valid PDF files: {}", valid_count)?;
     writeln!(file, "  Invalid PDF files: {}", invalid_count)?;
 
-    if results.len() > 0 {
+    let timed_out: Vec<_> = results
+        .iter()
+        .filter(|r| r.outcome.verdict == Verdict::TimedOut)
+        .collect();
+    if !timed_out.is_empty() {
+        writeln!(file, "  Timed out: {}", timed_out.len())?;
+    }
+
+    let mislabeled: Vec<_> = results.iter().filter(|r| r.mislabel.is_some()).collect();
+    if !mislabeled.is_empty() {
+        writeln!(file, "  Mislabeled: {}", mislabeled.len())?;
+    }
+    let dangling: Vec<_> = results
+        .iter()
+        .filter(|r| r.outcome.category == Some(FailureCategory::DanglingLink))
+        .collect();
+    if !dangling.is_empty() {
+        writeln!(file, "  Dangling links: {}", dangling.len())?;
+    }
+
+    if !results.is_empty() {
         let valid_pct = (valid_count as f64 / results.len() as f64) * 100.0;
         writeln!(file, "  Validation success rate: {:.2}%", valid_pct)?;
     }
 
     writeln!(file)?;
 
+    // Write verdict breakdown
+    let mut verdicts: BTreeMap<&'static str, usize> = BTreeMap::new();
+    let mut categories: BTreeMap<FailureCategory, usize> = BTreeMap::new();
+    for result in results {
+        *verdicts.entry(result.outcome.verdict.as_str()).or_insert(0) += 1;
+        if !result.is_valid() {
+            if let Some(category) = result.outcome.category {
+                *categories.entry(category).or_insert(0) += 1;
+            }
+        }
+    }
+
+    if results.iter().any(|r| r.outcome.verdict != Verdict::Valid) {
+        writeln!(file, "Verdicts:")?;
+        writeln!(file, "---------")?;
+        for (verdict, count) in &verdicts {
+            writeln!(file, "  {}: {}", verdict, count)?;
+        }
+        writeln!(file)?;
+    }
+
+    if !categories.is_empty() {
+        writeln!(file, "Failure Categories:")?;
+        writeln!(file, "-------------------")?;
+        for (category, count) in &categories {
+            writeln!(file, "  {}: {}", category, count)?;
+        }
+        writeln!(file)?;
+    }
+
+    // Write timed-out files separately so they can be triaged
+    if !timed_out.is_empty() {
+        writeln!(file, "Timed Out Files:")?;
+        writeln!(file, "----------------")?;
+        for result in &timed_out {
+            writeln!(file, "  {}", result.path.display())?;
+        }
+        writeln!(file)?;
+    }
+
+    // Write files whose name and content disagree
+    if !mislabeled.is_empty() {
+        writeln!(file, "Mislabeled Files:")?;
+        writeln!(file, "-----------------")?;
+        for result in &mislabeled {
+            if let Some(mislabel) = result.mislabel {
+                writeln!(file, "  {} ({})", result.path.display(), mislabel)?;
+            }
+            if result.outcome.category == Some(FailureCategory::Mislabeled) {
+                writeln!(file, "    {}", result.outcome)?;
+            }
+        }
+        writeln!(file)?;
+    }
+
+    // Write symlinks that point nowhere
+    if !dangling.is_empty() {
+        writeln!(file, "Dangling Links:")?;
+        writeln!(file, "---------------")?;
+        for result in &dangling {
+            writeln!(file, "  {}", result.path.display())?;
+            writeln!(file, "    {}", result.outcome)?;
+        }
+        writeln!(file)?;
+    }
+
     // Write invalid files
-    if invalid_count > 0 {
+    let invalid: Vec<_> = results.iter().filter(|r| is_listed_invalid(r)).collect();
+    if !invalid.is_empty() {
         writeln!(file, "Invalid Files:")?;
         writeln!(file, "--------------")?;
-        for result in results.iter().filter(|r| !r.is_valid) {
+        for result in invalid {
             writeln!(file, "  {}", result.path.display())?;
+            writeln!(file, "    {}", result.outcome)?;
+            for page in &result.outcome.failed_pages {
+                writeln!(file, "      {}", page)?;
+            }
+            for stream in &result.outcome.failed_streams {
+                writeln!(file, "      {}", stream)?;
+            }
         }
         writeln!(file)?;
     }
@@ -73,8 +169,9 @@ pub fn write_report(
             for (idx, dup) in dups.iter().enumerate() {
                 writeln!(file, "  Group {} (Hash: {}...):", idx + 1, &dup.hash[..16])?;
                 writeln!(file, "    Files ({} duplicates):", dup.paths.len())?;
-                for (file_idx, path) in dup.paths.iter().enumerate() {
-                    let marker = if file_idx == 0 { "[KEEP]" } else { "[DUP] " };
+                let keep = dup.keep();
+                for path in &dup.paths {
+                    let marker = if Some(path.as_path()) == keep { "[KEEP]" } else { "[DUP] " };
                     writeln!(file, "      {} {}", marker, path.display())?;
                 }
                 writeln!(file)?;
@@ -82,18 +179,106 @@ pub fn write_report(
         }
     }
 
+    write_root_breakdown(&mut file, results, duplicates)?;
+
     // Write valid files list
     writeln!(file, "Valid Files:")?;
     writeln!(file, "------------")?;
     writeln!(file, "  Total: {}", valid_count)?;
     writeln!(file)?;
-    for result in results.iter().filter(|r| r.is_valid) {
+    for result in results.iter().filter(|r| r.is_valid()) {
         writeln!(file, "  {}", result.path.display())?;
     }
 
     Ok(())
 }
 
+/// Whether a result belongs in the "Invalid Files" list
+///
+/// Timeouts, mislabeled files and dangling links have sections of their own.
+fn is_listed_invalid(result: &ValidationResult) -> bool {
+    !result.is_valid()
+        && result.outcome.verdict != Verdict::TimedOut
+        && result.outcome.category != Some(FailureCategory::Mislabeled)
+        && result.outcome.category != Some(FailureCategory::DanglingLink)
+}
+
+/// Write counts, invalid files and duplicate groups for each scan root
+///
+/// Only written when results come from more than one root. A duplicate
+/// group is listed under every root it has a file in.
+fn write_root_breakdown(
+    file: &mut File,
+    results: &[ValidationResult],
+    duplicates: Option<&[DuplicateInfo]>,
+) -> Result<()> {
+    let mut roots: BTreeMap<&Path, Vec<&ValidationResult>> = BTreeMap::new();
+    for result in results {
+        if let Some(ref root) = result.root {
+            roots.entry(root.as_path()).or_default().push(result);
+        }
+    }
+    if roots.len() < 2 {
+        return Ok(());
+    }
+
+    let root_of: HashMap<&Path, &Path> = results
+        .iter()
+        .filter_map(|r| r.root.as_deref().map(|root| (r.path.as_path(), root)))
+        .collect();
+    let duplicates = duplicates.unwrap_or(&[]);
+
+    writeln!(file, "Per-Root Summary:")?;
+    writeln!(file, "-----------------")?;
+    for (root, root_results) in &roots {
+        let valid = root_results.iter().filter(|r| r.is_valid()).count();
+        writeln!(file, "  {}", root.display())?;
+        writeln!(file, "    Total files scanned: {}", root_results.len())?;
+        writeln!(file, "    Valid PDF files: {}", valid)?;
+        writeln!(file, "    Invalid PDF files: {}", root_results.len() - valid)?;
+
+        let invalid: Vec<_> = root_results.iter().filter(|r| is_listed_invalid(r)).collect();
+        if !invalid.is_empty() {
+            writeln!(file, "    Invalid files:")?;
+            for result in invalid {
+                writeln!(file, "      {}", result.path.display())?;
+            }
+        }
+
+        // Group numbers match the "Duplicate Files" section
+        let groups: Vec<_> = duplicates
+            .iter()
+            .enumerate()
+            .filter(|(_, dup)| dup.paths.iter().any(|p| root_of.get(p.as_path()) == Some(root)))
+            .collect();
+        if !groups.is_empty() {
+            let shared = groups
+                .iter()
+                .filter(|(_, dup)| {
+                    dup.paths
+                        .iter()
+                        .any(|p| root_of.get(p.as_path()).is_some_and(|other| other != root))
+                })
+                .count();
+            writeln!(
+                file,
+                "    Duplicate groups: {} ({} shared with other roots)",
+                groups.len(),
+                shared
+            )?;
+            for (idx, dup) in groups {
+                writeln!(file, "      Group {}:", idx + 1)?;
+                for path in &dup.paths {
+                    writeln!(file, "        {}", path.display())?;
+                }
+            }
+        }
+        writeln!(file)?;
+    }
+
+    Ok(())
+}
+
 /// Write simple validation results (legacy format)
 ///
 /// # Arguments
@@ -103,7 +288,7 @@ pub fn write_simple_report(output_path: &Path, results: &[ValidationResult]) ->
     let mut file = File::create(output_path)?;
 
     for result in results {
-        let status = if result.is_valid { "VALID" } else { "INVALID" };
+        let status = if result.is_valid() { "VALID" } else { "INVALID" };
         writeln!(file, "{}: {}", status, result.path.display())?;
     }
 
@@ -113,6 +298,7 @@ pub fn write_simple_report(output_path: &Path, results: &[ValidationResult]) ->
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::core::outcome::{Strategy, ValidationOutcome, ValidationStage};
     use std::path::PathBuf;
     use tempfile::NamedTempFile;
 
@@ -121,34 +307,89 @@ mod tests {
         let temp_file = NamedTempFile::new().unwrap();
 
         let results = vec![
-            ValidationResult {
-                path: PathBuf::from("/test/valid.pdf"),
-                is_valid: true,
-            },
-            ValidationResult {
-                path: PathBuf::from("/test/invalid.pdf"),
-                is_valid: false,
-            },
+            ValidationResult::new(
+                PathBuf::from("/test/valid.pdf"),
+                ValidationOutcome::valid(Strategy::PdfRs),
+            ),
+            ValidationResult::new(
+                PathBuf::from("/test/invalid.pdf"),
+                ValidationOutcome::failure(
+                    FailureCategory::MissingEof,
+                    ValidationStage::QuickCheck,
+                    "Missing %%EOF marker",
+                ),
+            ),
         ];
 
         write_report(temp_file.path(), &results, None).unwrap();
 
         let content = std::fs::read_to_string(temp_file.path()).unwrap();
-        assert!(content.contains("Total files: 2"));
-        assert!(content.contains("Valid PDFs: 1"));
-        assert!(content.contains("Invalid PDFs: 1"));
+        assert!(content.contains("Total files scanned: 2"));
+        assert!(content.contains("Valid PDF files: 1"));
+        assert!(content.contains("Invalid PDF files: 1"));
+        assert!(content.contains("missing_eof: 1"));
+        assert!(content.contains("Missing %%EOF marker"));
     }
 
     #[test]
-    fn test_write_simple_report() {
+    fn test_write_report_per_root() {
         let temp_file = NamedTempFile::new().unwrap();
+        let in_root = |path: &str, root: &str, outcome: ValidationOutcome| {
+            ValidationResult::new(PathBuf::from(path), outcome).with_root(Some(PathBuf::from(root)))
+        };
+        let invalid = || ValidationOutcome::failure(FailureCategory::MissingEof, ValidationStage::QuickCheck, "x");
 
         let results = vec![
-            ValidationResult {
-                path: PathBuf::from("/test/valid.pdf"),
-                is_valid: true,
-            },
+            in_root("/a/one.pdf", "/a", ValidationOutcome::valid(Strategy::PdfRs)),
+            in_root("/a/bad.pdf", "/a", invalid()),
+            in_root("/b/two.pdf", "/b", ValidationOutcome::valid(Strategy::PdfRs)),
         ];
+        let duplicates = vec![DuplicateInfo {
+            hash: "0".repeat(64),
+            paths: vec![PathBuf::from("/a/one.pdf"), PathBuf::from("/b/two.pdf")],
+        }];
+
+        write_report(temp_file.path(), &results, Some(&duplicates)).unwrap();
+
+        let content = std::fs::read_to_string(temp_file.path()).unwrap();
+        let section = &content[content.find("Per-Root Summary:").unwrap()..];
+        let b_start = section.find("  /b\n").unwrap();
+        let (a, b) = section.split_at(b_start);
+        assert!(a.contains("Total files scanned: 2"));
+        assert!(a.contains("      /a/bad.pdf"));
+        assert!(a.contains("Duplicate groups: 1 (1 shared with other roots)"));
+        assert!(b.contains("Invalid PDF files: 0"));
+        assert!(b.contains("Duplicate groups: 1 (1 shared with other roots)"));
+    }
+
+    #[test]
+    fn test_write_report_never_keeps_archive_members() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let archive = temp_dir.path().join("a.zip");
+        std::fs::write(&archive, b"PK").unwrap();
+        let member = crate::scanner::archive::member_path(&archive, "x.pdf");
+        let on_disk = temp_dir.path().join("b.pdf");
+
+        let duplicates = vec![DuplicateInfo {
+            hash: "0".repeat(64),
+            paths: vec![member.clone(), on_disk.clone()],
+        }];
+        let output = temp_dir.path().join("report.txt");
+        write_report(&output, &[], Some(&duplicates)).unwrap();
+
+        let content = std::fs::read_to_string(&output).unwrap();
+        assert!(content.contains(&format!("[KEEP] {}", on_disk.display())));
+        assert!(content.contains(&format!("[DUP]  {}", member.display())));
+    }
+
+    #[test]
+    fn test_write_simple_report() {
+        let temp_file = NamedTempFile::new().unwrap();
+
+        let results = vec![ValidationResult::new(
+            PathBuf::from("/test/valid.pdf"),
+            ValidationOutcome::valid(Strategy::PdfRs),
+        )];
 
         write_simple_report(temp_file.path(), &results).unwrap();
 
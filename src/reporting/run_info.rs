@@ -0,0 +1,90 @@
+//! Description of a validation run for machine-readable reports
+
+use serde::{Serialize, Serializer};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+use super::timestamp::{serialize_rfc3339, serialize_rfc3339_opt};
+use crate::core::options::ValidationOptions;
+use crate::scanner::scan_options::{Discovery, ScanOptions};
+
+/// When, where and how a run validated its files
+#[derive(Debug, Clone, Serialize)]
+pub struct RunInfo {
+    /// Validator version
+    pub version: String,
+    #[serde(serialize_with = "serialize_rfc3339")]
+    pub started: SystemTime,
+    #[serde(serialize_with = "serialize_rfc3339")]
+    pub finished: SystemTime,
+    /// Number of validation threads
+    pub threads: usize,
+    /// Whether the run stopped before every discovered file was validated
+    pub interrupted: bool,
+    /// Scan roots (or the file list) of the run
+    pub roots: Vec<PathBuf>,
+    #[serde(serialize_with = "serialize_scan_options")]
+    pub scan: ScanOptions,
+    pub validation: ValidationOptions,
+    /// Other settings that shaped the run, such as `isolate` or `detect_duplicates`
+    pub settings: BTreeMap<String, serde_json::Value>,
+}
+
+impl RunInfo {
+    /// A run of this validator version started at `started`
+    pub fn new(started: SystemTime) -> Self {
+        Self {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            started,
+            finished: started,
+            threads: 1,
+            interrupted: false,
+            roots: Vec::new(),
+            scan: ScanOptions::default(),
+            validation: ValidationOptions::default(),
+            settings: BTreeMap::new(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct ScanOptionsRecord<'a> {
+    recursive: bool,
+    max_depth: Option<usize>,
+    include: &'a [String],
+    exclude: &'a [String],
+    use_ignore_files: bool,
+    min_size: Option<u64>,
+    max_size: Option<u64>,
+    #[serde(serialize_with = "serialize_rfc3339_opt")]
+    newer_than: Option<SystemTime>,
+    #[serde(serialize_with = "serialize_rfc3339_opt")]
+    older_than: Option<SystemTime>,
+    discovery: &'static str,
+    report_mislabeled: bool,
+    follow_symlinks: bool,
+    scan_archives: bool,
+}
+
+fn serialize_scan_options<S: Serializer>(options: &ScanOptions, serializer: S) -> Result<S::Ok, S::Error> {
+    ScanOptionsRecord {
+        recursive: options.recursive,
+        max_depth: options.max_depth,
+        include: &options.include,
+        exclude: &options.exclude,
+        use_ignore_files: options.use_ignore_files,
+        min_size: options.min_size,
+        max_size: options.max_size,
+        newer_than: options.newer_than,
+        older_than: options.older_than,
+        discovery: match options.discovery {
+            Discovery::Extension => "extension",
+            Discovery::Content => "content",
+        },
+        report_mislabeled: options.report_mislabeled,
+        follow_symlinks: options.follow_symlinks,
+        scan_archives: options.scan_archives,
+    }
+    .serialize(serializer)
+}
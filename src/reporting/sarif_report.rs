@@ -0,0 +1,181 @@
+//! SARIF 2.1.0 report for code-scanning tools
+
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use super::run_info::RunInfo;
+use super::timestamp::format_rfc3339;
+use crate::core::error::Result;
+use crate::core::outcome::{FailureCategory, Verdict};
+use crate::scanner::file_scanner::ValidationResult;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// Rule for non-valid results that carry no failure category
+const UNCATEGORIZED_RULE: &str = "uncategorized";
+
+/// Write one SARIF result per file that is not a valid PDF
+///
+/// Each failure category is a rule whose ID is the category's stable name
+/// (e.g. `missing_eof`). Invalid files are reported at level `error`;
+/// files that could not be validated or were skipped at level `warning`.
+///
+/// # Arguments
+/// * `output_path` - Path to output file
+/// * `run` - Metadata about the run
+/// * `results` - Validation results to write
+pub fn write_sarif_report(output_path: &Path, run: &RunInfo, results: &[ValidationResult]) -> Result<()> {
+    let failed: Vec<_> = results.iter().filter(|r| !r.is_valid()).collect();
+
+    // Rules in a stable order; results refer to them by index
+    let categories: BTreeMap<Option<FailureCategory>, usize> = failed
+        .iter()
+        .map(|r| r.outcome.category)
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .enumerate()
+        .map(|(index, category)| (category, index))
+        .collect();
+    let rules: Vec<Value> = categories.keys().map(|category| rule(*category)).collect();
+
+    let sarif_results: Vec<Value> = failed
+        .iter()
+        .map(|result| {
+            let outcome = &result.outcome;
+            let level = if outcome.verdict == Verdict::Invalid { "error" } else { "warning" };
+            let mut text = outcome.message.clone().unwrap_or_else(|| outcome.verdict.to_string());
+            if let Some(stage) = outcome.stage {
+                text = format!("{} (stage: {})", text, stage);
+            }
+            json!({
+                "ruleId": rule_id(outcome.category),
+                "ruleIndex": categories[&outcome.category],
+                "level": level,
+                "message": { "text": text },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": { "uri": path_to_uri(&result.path) }
+                    }
+                }],
+                "properties": { "verdict": outcome.verdict },
+            })
+        })
+        .collect();
+
+    let sarif = json!({
+        "$schema": SARIF_SCHEMA,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "pdf_validator_rs",
+                    "version": run.version,
+                    "informationUri": env!("CARGO_PKG_REPOSITORY"),
+                    "rules": rules,
+                }
+            },
+            "invocations": [{
+                "executionSuccessful": !run.interrupted,
+                "startTimeUtc": format_rfc3339(run.started),
+                "endTimeUtc": format_rfc3339(run.finished),
+            }],
+            "results": sarif_results,
+            "properties": {
+                "filesValidated": results.len(),
+                "roots": run.roots,
+            },
+        }],
+    });
+
+    let mut writer = BufWriter::new(File::create(output_path)?);
+    serde_json::to_writer_pretty(&mut writer, &sarif)?;
+    writeln!(writer)?;
+    writer.flush()?;
+    Ok(())
+}
+
+fn rule_id(category: Option<FailureCategory>) -> &'static str {
+    category.map(|c| c.as_str()).unwrap_or(UNCATEGORIZED_RULE)
+}
+
+fn rule(category: Option<FailureCategory>) -> Value {
+    let description = category
+        .map(|c| c.description())
+        .unwrap_or("File could not be validated");
+    json!({
+        "id": rule_id(category),
+        "shortDescription": { "text": description },
+    })
+}
+
+/// URI of a reported path: relative paths stay relative, absolute ones become `file://` URIs
+fn path_to_uri(path: &Path) -> String {
+    let text = path.to_string_lossy().replace('\\', "/");
+    let mut uri = String::with_capacity(text.len());
+    for byte in text.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' | b'!' | b':' => {
+                uri.push(byte as char)
+            }
+            _ => uri.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    if path.is_absolute() {
+        // Windows drive paths (C:/...) need a leading slash
+        let slash = if uri.starts_with('/') { "" } else { "/" };
+        format!("file://{}{}", slash, uri)
+    } else {
+        uri
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::outcome::{Strategy, ValidationOutcome, ValidationStage};
+    use std::path::PathBuf;
+    use std::time::Duration;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn test_path_to_uri() {
+        assert_eq!(path_to_uri(Path::new("docs/a b.pdf")), "docs/a%20b.pdf");
+        #[cfg(unix)]
+        assert_eq!(path_to_uri(Path::new("/srv/%x.pdf")), "file:///srv/%25x.pdf");
+    }
+
+    #[test]
+    fn test_write_sarif_report() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let results = vec![
+            ValidationResult::new(PathBuf::from("docs/a.pdf"), ValidationOutcome::valid(Strategy::PdfRs)),
+            ValidationResult::new(
+                PathBuf::from("docs/b.pdf"),
+                ValidationOutcome::failure(FailureCategory::MissingEof, ValidationStage::QuickCheck, "Missing %%EOF marker"),
+            ),
+            ValidationResult::new(PathBuf::from("docs/c.pdf"), ValidationOutcome::timed_out(Duration::from_secs(5))),
+        ];
+
+        write_sarif_report(temp_file.path(), &RunInfo::new(std::time::SystemTime::now()), &results).unwrap();
+
+        let sarif: Value = serde_json::from_reader(File::open(temp_file.path()).unwrap()).unwrap();
+        assert_eq!(sarif["version"], "2.1.0");
+        let run = &sarif["runs"][0];
+        let rules = run["tool"]["driver"]["rules"].as_array().unwrap();
+        assert_eq!(rules.len(), 2);
+        assert_eq!(rules[0]["id"], "missing_eof");
+        assert_eq!(rules[1]["id"], "timeout");
+
+        let found = run["results"].as_array().unwrap();
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[0]["ruleId"], "missing_eof");
+        assert_eq!(found[0]["ruleIndex"], 0);
+        assert_eq!(found[0]["level"], "error");
+        assert_eq!(found[0]["locations"][0]["physicalLocation"]["artifactLocation"]["uri"], "docs/b.pdf");
+        assert_eq!(found[1]["ruleId"], "timeout");
+        assert_eq!(found[1]["level"], "warning");
+    }
+}
@@ -0,0 +1,430 @@
+//! Finding and validating PDFs inside ZIP and TAR archives
+
+use flate2::read::GzDecoder;
+use std::cell::Cell;
+use std::fs::File;
+use std::io::{self, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use super::content_sniffer::{find_pdf_header, has_pdf_extension, SNIFF_WINDOW};
+use super::duplicate_detector::compute_data_hash;
+use super::file_scanner::ValidationResult;
+use super::scan_options::Discovery;
+use crate::core::error::{Result, ValidationError};
+use crate::core::options::ValidationOptions;
+use crate::core::outcome::{ValidationOutcome, ValidationStage};
+use crate::core::validator::Validator;
+
+/// Separates the archive path from the member name in reported paths
+pub const MEMBER_SEPARATOR: &str = "!/";
+
+/// Default cap on the uncompressed bytes read from one archive (8 GiB)
+pub const DEFAULT_ARCHIVE_BUDGET: u64 = 8 << 30;
+
+/// Supported archive formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ArchiveKind {
+    Zip,
+    Tar,
+    /// Gzip-compressed tar (`.tar.gz` or `.tgz`)
+    TarGz,
+}
+
+impl ArchiveKind {
+    /// Archive format implied by the file name, if any
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_string_lossy().to_ascii_lowercase();
+        if name.ends_with(".zip") {
+            Some(ArchiveKind::Zip)
+        } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            Some(ArchiveKind::TarGz)
+        } else if name.ends_with(".tar") {
+            Some(ArchiveKind::Tar)
+        } else {
+            None
+        }
+    }
+}
+
+/// How much of an archive is read
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ArchiveLimits {
+    /// Members larger than this are passed without their data (bytes)
+    pub member: u64,
+    /// Reading fails once this many uncompressed bytes have come out of
+    /// the archive, so a compression bomb cannot run unbounded (bytes)
+    pub total: u64,
+}
+
+impl ArchiveLimits {
+    /// Limits for validating members with `options`
+    ///
+    /// A member must fit both the file size ceiling and the memory budget,
+    /// since it is held in memory while it is validated.
+    pub fn new(options: &ValidationOptions) -> Self {
+        Self {
+            member: options.max_file_size.min(options.memory_limit.unwrap_or(u64::MAX)),
+            total: DEFAULT_ARCHIVE_BUDGET,
+        }
+    }
+}
+
+/// A PDF found inside an archive
+#[derive(Debug, Clone)]
+pub struct ArchiveMember {
+    /// Path of the member within the archive
+    pub name: String,
+    /// Uncompressed size recorded in the archive
+    pub size: u64,
+    /// Member content; empty when `size` exceeds the member limit
+    pub data: Vec<u8>,
+}
+
+/// Path reported for an archive member, e.g. `bundle.zip!/docs/a.pdf`
+pub fn member_path(archive: &Path, name: &str) -> PathBuf {
+    let mut path = archive.as_os_str().to_owned();
+    path.push(MEMBER_SEPARATOR);
+    path.push(name.trim_start_matches('/'));
+    PathBuf::from(path)
+}
+
+/// Split a member path into the archive on disk and the member name
+///
+/// Returns `None` for ordinary paths, including ones that merely contain
+/// `!/` without an archive file in front of it.
+pub fn split_member_path(path: &Path) -> Option<(PathBuf, String)> {
+    let text = path.to_str()?;
+    text.match_indices(MEMBER_SEPARATOR)
+        .map(|(i, _)| (&text[..i], &text[i + MEMBER_SEPARATOR.len()..]))
+        .find(|(archive, _)| Path::new(archive).is_file())
+        .map(|(archive, name)| (PathBuf::from(archive), name.to_string()))
+}
+
+/// Whether `path` names a file inside an archive rather than on disk
+pub fn is_member_path(path: &Path) -> bool {
+    split_member_path(path).is_some()
+}
+
+/// Call `visit` for every PDF member of the archive at `path`
+///
+/// Members named `*.pdf` are PDFs; with [`Discovery::Content`] so is any
+/// member whose first 1024 bytes contain a PDF header. Members larger than
+/// `limits.member` are passed without their data, and reading stops with an
+/// error once more than `limits.total` uncompressed bytes have been read.
+pub fn for_each_pdf_member(
+    path: &Path,
+    kind: ArchiveKind,
+    discovery: Discovery,
+    limits: ArchiveLimits,
+    mut visit: impl FnMut(ArchiveMember),
+) -> Result<()> {
+    let read = Cell::new(0);
+    match kind {
+        ArchiveKind::Zip => {
+            let mut archive = zip::ZipArchive::new(BufReader::new(File::open(path)?))?;
+            for index in 0..archive.len() {
+                let member = archive.by_index(index)?;
+                if member.is_dir() {
+                    continue;
+                }
+                let name = member.name().to_string();
+                let size = member.size();
+                let member = Budgeted::new(member, &read, limits.total);
+                if let Some(found) = read_member(member, name, size, discovery, limits.member)? {
+                    visit(found);
+                }
+            }
+            Ok(())
+        }
+        // Tar entries are skipped by reading through them, so the whole
+        // stream counts against the budget
+        ArchiveKind::Tar => visit_tar(
+            Budgeted::new(BufReader::new(File::open(path)?), &read, limits.total),
+            discovery,
+            limits.member,
+            visit,
+        ),
+        ArchiveKind::TarGz => visit_tar(
+            Budgeted::new(GzDecoder::new(BufReader::new(File::open(path)?)), &read, limits.total),
+            discovery,
+            limits.member,
+            visit,
+        ),
+    }
+}
+
+/// Reader that fails once the bytes read through it and its siblings
+/// sharing `read` pass `limit`
+struct Budgeted<'a, R> {
+    inner: R,
+    read: &'a Cell<u64>,
+    limit: u64,
+}
+
+impl<'a, R: Read> Budgeted<'a, R> {
+    fn new(inner: R, read: &'a Cell<u64>, limit: u64) -> Self {
+        Self { inner, read, limit }
+    }
+}
+
+impl<R: Read> Read for Budgeted<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.read.set(self.read.get() + n as u64);
+        if self.read.get() > self.limit {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("archive expands to more than {} bytes", self.limit),
+            ));
+        }
+        Ok(n)
+    }
+}
+
+fn visit_tar(
+    reader: impl Read,
+    discovery: Discovery,
+    read_limit: u64,
+    mut visit: impl FnMut(ArchiveMember),
+) -> Result<()> {
+    let mut archive = tar::Archive::new(reader);
+    for entry in archive.entries()? {
+        let entry = entry?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        let name = entry.path()?.to_string_lossy().into_owned();
+        let size = entry.size();
+        if let Some(found) = read_member(entry, name, size, discovery, read_limit)? {
+            visit(found);
+        }
+    }
+    Ok(())
+}
+
+/// Read a member if it is a PDF
+fn read_member(
+    reader: impl Read,
+    name: String,
+    size: u64,
+    discovery: Discovery,
+    read_limit: u64,
+) -> io::Result<Option<ArchiveMember>> {
+    let named_pdf = has_pdf_extension(Path::new(&name));
+    if !named_pdf && discovery == Discovery::Extension {
+        return Ok(None);
+    }
+
+    // The recorded size can lie, so never read more than one byte past the
+    // limit either way; that byte shows the member is really larger
+    let limit = if size > read_limit { SNIFF_WINDOW as u64 } else { read_limit.saturating_add(1) };
+    let mut data = Vec::new();
+    reader.take(limit).read_to_end(&mut data)?;
+    if !named_pdf && find_pdf_header(&data).is_none() {
+        return Ok(None);
+    }
+    let size = size.max(data.len() as u64);
+    if size > read_limit {
+        data = Vec::new();
+    }
+
+    Ok(Some(ArchiveMember { name, size, data }))
+}
+
+/// Validate every PDF member of an archive in memory
+///
+/// Each result is reported under the member's path (see [`member_path`])
+/// and carries the member's size and the SHA-256 of its content. If the archive cannot be read,
+/// a final result for the archive itself records the error.
+pub fn validate_archive(
+    validator: &Validator,
+    path: &Path,
+    kind: ArchiveKind,
+    discovery: Discovery,
+) -> Vec<ValidationResult> {
+    validate_archive_with(validator.options(), path, kind, discovery, |_, data| validator.validate_bytes(data))
+}
+
+/// [`validate_archive`] with the members validated by `validate`
+///
+/// `validate` receives each member's reported path and content, so the
+/// members can be handed to isolated workers instead of being validated
+/// in this process.
+pub fn validate_archive_with(
+    options: &ValidationOptions,
+    path: &Path,
+    kind: ArchiveKind,
+    discovery: Discovery,
+    mut validate: impl FnMut(&Path, &[u8]) -> ValidationOutcome,
+) -> Vec<ValidationResult> {
+    let limits = ArchiveLimits::new(options);
+    let mut results = Vec::new();
+
+    let walked = for_each_pdf_member(path, kind, discovery, limits, |member| {
+        let member_path = member_path(path, &member.name);
+        let started = Instant::now();
+        let result = if member.size > limits.member {
+            let error = if member.size > options.max_file_size {
+                ValidationError::TooLarge {
+                    size: member.size,
+                    limit: options.max_file_size,
+                }
+            } else {
+                ValidationError::MemoryLimit {
+                    needed: member.size,
+                    limit: limits.member,
+                }
+            };
+            ValidationResult::new(member_path, error_outcome(&error))
+        } else {
+            let outcome = validate(&member_path, &member.data);
+            ValidationResult::new(member_path, outcome)
+                .with_sha256(Some(compute_data_hash(&member.data)))
+        };
+        results.push(result.with_size(Some(member.size)).with_duration(started.elapsed()));
+    });
+
+    if let Err(e) = walked {
+        let outcome = ValidationOutcome::failure(
+            e.category(),
+            e.stage().unwrap_or(ValidationStage::QuickCheck),
+            format!("Failed to read archive: {}", e),
+        );
+        results.push(ValidationResult::new(path.to_path_buf(), outcome));
+    }
+
+    results
+}
+
+fn error_outcome(error: &ValidationError) -> ValidationOutcome {
+    ValidationOutcome::failure(
+        error.category(),
+        error.stage().unwrap_or(ValidationStage::QuickCheck),
+        error.to_string(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::TempDir;
+
+    fn limits(member: u64) -> ArchiveLimits {
+        ArchiveLimits {
+            member,
+            total: u64::MAX,
+        }
+    }
+
+    fn write_zip(path: &Path, members: &[(&str, &[u8])]) {
+        let mut zip = zip::ZipWriter::new(File::create(path).unwrap());
+        for (name, data) in members {
+            zip.start_file(*name, zip::write::SimpleFileOptions::default()).unwrap();
+            zip.write_all(data).unwrap();
+        }
+        zip.finish().unwrap();
+    }
+
+    #[test]
+    fn test_archive_kind_and_member_paths() {
+        assert_eq!(ArchiveKind::from_path(Path::new("a/Bundle.ZIP")), Some(ArchiveKind::Zip));
+        assert_eq!(ArchiveKind::from_path(Path::new("b.tar.gz")), Some(ArchiveKind::TarGz));
+        assert_eq!(ArchiveKind::from_path(Path::new("c.tgz")), Some(ArchiveKind::TarGz));
+        assert_eq!(ArchiveKind::from_path(Path::new("d.pdf")), None);
+
+        let temp_dir = TempDir::new().unwrap();
+        let archive = temp_dir.path().join("bundle.zip");
+        File::create(&archive).unwrap();
+        let path = member_path(&archive, "docs/a.pdf");
+        assert!(path.to_string_lossy().ends_with("bundle.zip!/docs/a.pdf"));
+        assert_eq!(split_member_path(&path), Some((archive, "docs/a.pdf".to_string())));
+        assert!(!is_member_path(Path::new("/no/such!/file.pdf")));
+    }
+
+    #[test]
+    fn test_for_each_pdf_member_zip() {
+        let temp_dir = TempDir::new().unwrap();
+        let archive = temp_dir.path().join("bundle.zip");
+        write_zip(
+            &archive,
+            &[
+                ("docs/a.pdf", b"%PDF-1.4 a"),
+                ("docs/readme.txt", b"hello"),
+                ("scans/0001", b"%PDF-1.7 b"),
+            ],
+        );
+
+        let mut names = Vec::new();
+        for_each_pdf_member(&archive, ArchiveKind::Zip, Discovery::Extension, limits(1024), |m| names.push(m.name)).unwrap();
+        assert_eq!(names, vec!["docs/a.pdf"]);
+
+        let mut members = Vec::new();
+        for_each_pdf_member(&archive, ArchiveKind::Zip, Discovery::Content, limits(1024), |m| members.push(m)).unwrap();
+        assert_eq!(members.len(), 2);
+        assert_eq!(members[1].data, b"%PDF-1.7 b");
+
+        let mut sizes = Vec::new();
+        for_each_pdf_member(&archive, ArchiveKind::Zip, Discovery::Extension, limits(4), |m| sizes.push((m.size, m.data.len())))
+            .unwrap();
+        assert_eq!(sizes, vec![(10, 0)]);
+    }
+
+    #[test]
+    fn test_for_each_pdf_member_tar_gz() {
+        let temp_dir = TempDir::new().unwrap();
+        let archive = temp_dir.path().join("bundle.tar.gz");
+        let encoder = flate2::write::GzEncoder::new(File::create(&archive).unwrap(), flate2::Compression::default());
+        let mut builder = tar::Builder::new(encoder);
+        let data = b"%PDF-1.4 tar";
+        let mut header = tar::Header::new_gnu();
+        header.set_size(data.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder.append_data(&mut header, "inner/a.pdf", &data[..]).unwrap();
+        builder.into_inner().unwrap().finish().unwrap();
+
+        let mut members = Vec::new();
+        for_each_pdf_member(&archive, ArchiveKind::TarGz, Discovery::Extension, limits(1024), |m| members.push(m)).unwrap();
+        assert_eq!(members.len(), 1);
+        assert_eq!(members[0].name, "inner/a.pdf");
+        assert_eq!(members[0].data, data);
+    }
+
+    #[test]
+    fn test_archive_budget_stops_expansion() {
+        let temp_dir = TempDir::new().unwrap();
+        let archive = temp_dir.path().join("bomb.zip");
+        let zeros = vec![0u8; 4096];
+        write_zip(&archive, &[("a.pdf", &zeros), ("b.pdf", &zeros)]);
+
+        let budget = ArchiveLimits { member: 8192, total: 6000 };
+        let mut names = Vec::new();
+        let walked = for_each_pdf_member(&archive, ArchiveKind::Zip, Discovery::Extension, budget, |m| names.push(m.name));
+        assert!(walked.is_err());
+        assert_eq!(names, vec!["a.pdf"]);
+    }
+
+    #[test]
+    fn test_members_over_memory_budget_are_not_read() {
+        use crate::core::outcome::FailureCategory;
+
+        let temp_dir = TempDir::new().unwrap();
+        let archive = temp_dir.path().join("bundle.zip");
+        write_zip(&archive, &[("a.pdf", &[b' '; 4096])]);
+
+        let options = ValidationOptions {
+            memory_limit: Some(1024),
+            ..ValidationOptions::default()
+        };
+        let mut validated = 0;
+        let results = validate_archive_with(&options, &archive, ArchiveKind::Zip, Discovery::Extension, |_, _| {
+            validated += 1;
+            ValidationOutcome::failure(FailureCategory::ParseError, ValidationStage::Parse, "unreachable")
+        });
+        assert_eq!(validated, 0);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].outcome.category, Some(FailureCategory::MemoryLimit));
+    }
+}
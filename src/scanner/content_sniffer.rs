@@ -0,0 +1,104 @@
+//! Recognising PDFs by content rather than by name
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path;
+
+pub use crate::core::header::{find_pdf_header, SNIFF_WINDOW};
+
+/// Ways a file's name and content can disagree
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Mislabel {
+    /// PDF content under a name without a `.pdf` extension
+    PdfWithoutExtension,
+    /// A `.pdf` name on content that is not a PDF
+    NotPdf,
+}
+
+impl Mislabel {
+    /// Stable machine-readable name
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Mislabel::PdfWithoutExtension => "pdf_without_extension",
+            Mislabel::NotPdf => "not_pdf",
+        }
+    }
+}
+
+impl fmt::Display for Mislabel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Whether the file name ends in `.pdf` (any case)
+pub fn has_pdf_extension(path: &Path) -> bool {
+    path.extension()
+        .map(|ext| ext.to_string_lossy().eq_ignore_ascii_case("pdf"))
+        .unwrap_or(false)
+}
+
+/// Read the start of a file
+pub fn read_prefix(path: &Path) -> io::Result<Vec<u8>> {
+    let mut prefix = Vec::with_capacity(SNIFF_WINDOW);
+    File::open(path)?
+        .take(SNIFF_WINDOW as u64)
+        .read_to_end(&mut prefix)?;
+    Ok(prefix)
+}
+
+/// Whether the file's content starts like a PDF
+pub fn sniff_pdf(path: &Path) -> io::Result<bool> {
+    Ok(find_pdf_header(&read_prefix(path)?).is_some())
+}
+
+/// Short description of non-PDF content, for reports
+pub fn describe_content(prefix: &[u8]) -> &'static str {
+    let start = prefix
+        .iter()
+        .position(|b| !b.is_ascii_whitespace())
+        .map(|i| &prefix[i..])
+        .unwrap_or(&[]);
+    let lower: Vec<u8> = start.iter().take(16).map(u8::to_ascii_lowercase).collect();
+
+    if start.is_empty() {
+        "empty file"
+    } else if lower.starts_with(b"<!doctype html") || lower.starts_with(b"<html") {
+        "HTML document"
+    } else if lower.starts_with(b"<?xml") {
+        "XML document"
+    } else if start.starts_with(b"PK\x03\x04") {
+        "ZIP archive"
+    } else if start.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        "JPEG image"
+    } else if start.starts_with(b"\x89PNG") {
+        "PNG image"
+    } else {
+        "no %PDF- header in the first 1024 bytes"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_pdf_header_allows_leading_junk() {
+        assert_eq!(find_pdf_header(b"%PDF-1.7\n"), Some(0));
+        assert_eq!(find_pdf_header(b"\xEF\xBB\xBFjunk\r\n%PDF-1.4"), Some(9));
+
+        let mut late = vec![b' '; SNIFF_WINDOW];
+        late.extend_from_slice(b"%PDF-1.4");
+        assert_eq!(find_pdf_header(&late), None);
+    }
+
+    #[test]
+    fn test_describe_content() {
+        assert_eq!(describe_content(b"\n  <!DOCTYPE html><html>"), "HTML document");
+        assert_eq!(describe_content(b"PK\x03\x04rest"), "ZIP archive");
+        assert_eq!(describe_content(b""), "empty file");
+    }
+}
@@ -1,12 +1,15 @@
 //! Duplicate file detection using SHA-256 hashing
 
-use anyhow::Result;
+use crate::core::error::Result;
 use sha2::{Sha256, Digest};
 use std::collections::HashMap;
 use std::fs::File;
 use std::io::Read;
 use std::path::{Path, PathBuf};
 
+use super::archive::is_member_path;
+use super::file_scanner::ValidationResult;
+
 /// Information about a duplicate file
 #[derive(Debug, Clone)]
 pub struct DuplicateInfo {
@@ -14,6 +17,16 @@ pub struct DuplicateInfo {
     pub paths: Vec<PathBuf>,
 }
 
+impl DuplicateInfo {
+    /// The copy `--delete-duplicates` keeps: the first one on disk
+    ///
+    /// Archive members cannot stand in for a file, so a group made only of
+    /// members has no copy to keep and nothing in it is deleted.
+    pub fn keep(&self) -> Option<&Path> {
+        self.paths.iter().map(PathBuf::as_path).find(|path| !is_member_path(path))
+    }
+}
+
 /// Compute SHA-256 hash of a file
 ///
 /// # Arguments
@@ -38,6 +51,11 @@ pub fn compute_file_hash(path: &Path) -> Result<String> {
     Ok(format!("{:x}", result))
 }
 
+/// Compute SHA-256 hash of data held in memory
+pub fn compute_data_hash(data: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(data))
+}
+
 /// Find duplicate files in a list of paths
 ///
 /// # Arguments
@@ -46,21 +64,48 @@ pub fn compute_file_hash(path: &Path) -> Result<String> {
 /// # Returns
 /// Vector of DuplicateInfo containing files with identical hashes
 pub fn find_duplicates(paths: &[PathBuf]) -> Result<Vec<DuplicateInfo>> {
+    let hashed = paths
+        .iter()
+        .filter_map(|path| compute_file_hash(path).ok().map(|hash| (path.clone(), hash)));
+    Ok(group_by_hash(hashed))
+}
+
+/// Find duplicates among validation results
+///
+/// Uses the hash recorded on a result (as for archive members, which have
+/// no file of their own) and hashes the file on disk otherwise.
+pub fn find_result_duplicates<'a>(results: impl IntoIterator<Item = &'a ValidationResult>) -> Vec<DuplicateInfo> {
+    let hashed = results.into_iter().filter_map(|result| {
+        let hash = match result.sha256 {
+            Some(ref hash) => hash.clone(),
+            None => compute_file_hash(&result.path).ok()?,
+        };
+        Some((result.path.clone(), hash))
+    });
+    group_by_hash(hashed)
+}
+
+/// Group paths sharing a hash, keeping only groups with more than one path
+///
+/// Paths within a group are sorted, and groups are ordered by their first
+/// path, so the result does not depend on the order paths were found in.
+fn group_by_hash(hashed: impl IntoIterator<Item = (PathBuf, String)>) -> Vec<DuplicateInfo> {
     let mut hash_map: HashMap<String, Vec<PathBuf>> = HashMap::new();
 
-    for path in paths {
-        if let Ok(hash) = compute_file_hash(path) {
-            hash_map.entry(hash).or_insert_with(Vec::new).push(path.clone());
-        }
+    for (path, hash) in hashed {
+        hash_map.entry(hash).or_default().push(path);
     }
 
-    let duplicates: Vec<DuplicateInfo> = hash_map
+    let mut groups: Vec<DuplicateInfo> = hash_map
         .into_iter()
         .filter(|(_, paths)| paths.len() > 1)
-        .map(|(hash, paths)| DuplicateInfo { hash, paths })
+        .map(|(hash, mut paths)| {
+            paths.sort();
+            DuplicateInfo { hash, paths }
+        })
         .collect();
-
-    Ok(duplicates)
+    groups.sort_by(|a, b| a.paths.cmp(&b.paths));
+    groups
 }
 
 #[cfg(test)]
@@ -98,5 +143,46 @@ mod tests {
         let duplicates = find_duplicates(&paths).unwrap();
         assert_eq!(duplicates.len(), 1);
         assert_eq!(duplicates[0].paths.len(), 2);
+
+        // Groups come out the same whatever order the paths went in
+        let reversed: Vec<PathBuf> = paths.iter().rev().cloned().collect();
+        assert_eq!(find_duplicates(&reversed).unwrap()[0].paths, duplicates[0].paths);
+        assert!(duplicates[0].paths.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    fn test_find_result_duplicates_uses_recorded_hash() {
+        use crate::core::outcome::{Strategy, ValidationOutcome};
+
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(b"same content").unwrap();
+
+        let on_disk = ValidationResult::new(file.path().to_path_buf(), ValidationOutcome::valid(Strategy::PdfRs));
+        let member = ValidationResult::new(PathBuf::from("bundle.zip!/a.pdf"), ValidationOutcome::valid(Strategy::PdfRs))
+            .with_sha256(Some(compute_data_hash(b"same content")));
+
+        let duplicates = find_result_duplicates([&on_disk, &member]);
+        assert_eq!(duplicates.len(), 1);
+        assert_eq!(duplicates[0].paths.len(), 2);
+    }
+
+    #[test]
+    fn test_keep_skips_archive_members() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let archive = temp_dir.path().join("a.zip");
+        std::fs::write(&archive, b"PK").unwrap();
+        let member = crate::scanner::archive::member_path(&archive, "x.pdf");
+
+        let group = DuplicateInfo {
+            hash: "abc".to_string(),
+            paths: vec![member.clone(), PathBuf::from("b/x.pdf"), PathBuf::from("c/x.pdf")],
+        };
+        assert_eq!(group.keep(), Some(Path::new("b/x.pdf")));
+
+        let members_only = DuplicateInfo {
+            hash: "abc".to_string(),
+            paths: vec![member],
+        };
+        assert_eq!(members_only.keep(), None);
     }
 }
@@ -0,0 +1,62 @@
+//! Explicit lists of files to validate
+
+use crate::core::error::Result;
+use std::io::Read;
+use std::path::PathBuf;
+
+/// Parse a list of paths separated by newlines or NUL bytes
+///
+/// If the list contains a NUL byte it is split on NULs only, so output of
+/// `find -print0` works even for names containing newlines. Otherwise it is
+/// split into lines with any trailing `\r` removed. Empty entries are skipped.
+pub fn parse_file_list(data: &[u8]) -> Vec<PathBuf> {
+    let entries: Vec<&[u8]> = if data.contains(&0) {
+        data.split(|&b| b == 0).collect()
+    } else {
+        data.split(|&b| b == b'\n')
+            .map(|line| line.strip_suffix(b"\r").unwrap_or(line))
+            .collect()
+    };
+
+    entries
+        .into_iter()
+        .filter(|entry| !entry.is_empty())
+        .map(path_from_bytes)
+        .collect()
+}
+
+/// Read a newline- or NUL-separated list of paths
+pub fn read_file_list<R: Read>(mut reader: R) -> Result<Vec<PathBuf>> {
+    let mut data = Vec::new();
+    reader.read_to_end(&mut data)?;
+    Ok(parse_file_list(&data))
+}
+
+#[cfg(unix)]
+fn path_from_bytes(bytes: &[u8]) -> PathBuf {
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+    PathBuf::from(OsStr::from_bytes(bytes))
+}
+
+#[cfg(not(unix))]
+fn path_from_bytes(bytes: &[u8]) -> PathBuf {
+    PathBuf::from(String::from_utf8_lossy(bytes).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_newline_separated() {
+        let files = parse_file_list(b"a.pdf\r\n\nsub dir/b.pdf\n");
+        assert_eq!(files, vec![PathBuf::from("a.pdf"), PathBuf::from("sub dir/b.pdf")]);
+    }
+
+    #[test]
+    fn test_nul_separated_keeps_newlines_in_names() {
+        let files = parse_file_list(b"./a.pdf\0./odd\nname.pdf\0");
+        assert_eq!(files, vec![PathBuf::from("./a.pdf"), PathBuf::from("./odd\nname.pdf")]);
+    }
+}
@@ -1,15 +1,97 @@
 //! PDF file scanning and collection
 
-use anyhow::Result;
-use std::fs;
+use crate::core::error::Result;
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use ignore::overrides::OverrideBuilder;
+use ignore::{DirEntry, WalkBuilder, WalkState};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs::{self, Metadata};
+use std::io;
 use std::path::{Path, PathBuf};
-use walkdir::WalkDir;
+use std::sync::mpsc::{self, Receiver};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use super::archive::ArchiveKind;
+use super::content_sniffer::{has_pdf_extension, sniff_pdf, Mislabel};
+use super::scan_options::{Discovery, ScanOptions, IGNORE_FILE_NAME};
+use crate::core::outcome::ValidationOutcome;
 
 /// Result of validating a single PDF file
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ValidationResult {
     pub path: PathBuf,
-    pub is_valid: bool,
+    pub outcome: ValidationOutcome,
+    /// Set when the file's name and content disagree
+    #[serde(default)]
+    pub mislabel: Option<Mislabel>,
+    /// Scan root (directory or file given on the command line) the file came from
+    #[serde(default)]
+    pub root: Option<PathBuf>,
+    /// SHA-256 of the content, when it was hashed during validation
+    #[serde(default)]
+    pub sha256: Option<String>,
+    /// Size of the file (or archive member) in bytes
+    #[serde(default)]
+    pub size: Option<u64>,
+    /// Modification time of the file when it was validated
+    #[serde(default)]
+    pub modified: Option<SystemTime>,
+    /// Time spent validating the file
+    #[serde(default)]
+    pub duration: Option<Duration>,
+}
+
+impl ValidationResult {
+    pub fn new(path: PathBuf, outcome: ValidationOutcome) -> Self {
+        Self {
+            path,
+            outcome,
+            mislabel: None,
+            root: None,
+            sha256: None,
+            size: None,
+            modified: None,
+            duration: None,
+        }
+    }
+
+    pub fn with_mislabel(mut self, mislabel: Option<Mislabel>) -> Self {
+        self.mislabel = mislabel;
+        self
+    }
+
+    pub fn with_root(mut self, root: Option<PathBuf>) -> Self {
+        self.root = root;
+        self
+    }
+
+    pub fn with_sha256(mut self, sha256: Option<String>) -> Self {
+        self.sha256 = sha256;
+        self
+    }
+
+    pub fn with_size(mut self, size: Option<u64>) -> Self {
+        self.size = size;
+        self
+    }
+
+    pub fn with_modified(mut self, modified: Option<SystemTime>) -> Self {
+        self.modified = modified;
+        self
+    }
+
+    pub fn with_duration(mut self, duration: Duration) -> Self {
+        self.duration = Some(duration);
+        self
+    }
+
+    /// Whether the file counts as a valid PDF
+    pub fn is_valid(&self) -> bool {
+        self.outcome.is_valid()
+    }
 }
 
 /// Collect all PDF files from a directory
@@ -21,39 +103,347 @@ pub struct ValidationResult {
 /// # Returns
 /// Vector of PDF file paths
 pub fn collect_pdf_files(dir: &Path, recursive: bool) -> Result<Vec<PathBuf>> {
-    let mut pdf_files = Vec::new();
-
-    if recursive {
-        for entry in WalkDir::new(dir).follow_links(false) {
-            let entry = entry?;
-            if entry.file_type().is_file() {
-                if let Some(ext) = entry.path().extension() {
-                    if ext.to_string_lossy().to_lowercase() == "pdf" {
-                        pdf_files.push(entry.path().to_path_buf());
-                    }
-                }
-            }
-        }
-    } else {
-        for entry in fs::read_dir(dir)? {
-            let entry = entry?;
-            if entry.file_type()?.is_file() {
-                if let Some(ext) = entry.path().extension() {
-                    if ext.to_string_lossy().to_lowercase() == "pdf" {
-                        pdf_files.push(entry.path());
+    let options = ScanOptions {
+        recursive,
+        ..ScanOptions::default()
+    };
+    Ok(scan(dir, &options)?.into_iter().map(|found| found.path).collect())
+}
+
+/// Capacity of the channel between the parallel walker and its consumer
+const STREAM_BUFFER: usize = 1024;
+
+/// A file picked up by [`scan`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiscoveredFile {
+    pub path: PathBuf,
+    /// Set when name and content disagree (only if requested)
+    pub mislabel: Option<Mislabel>,
+    /// Where the symlink at `path` points when that target does not exist
+    pub dangling_target: Option<PathBuf>,
+    /// Root passed to the scan that found the file
+    pub root: Option<PathBuf>,
+    /// Set when the file is an archive whose PDF members should be validated
+    pub archive: Option<ArchiveKind>,
+}
+
+impl DiscoveredFile {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            mislabel: None,
+            dangling_target: None,
+            root: None,
+            archive: None,
+        }
+    }
+}
+
+/// Collect the candidate files under `root` selected by `options`
+///
+/// Directories matching an exclude glob or a `.pdfvalidatorignore` rule
+/// are not descended into. `.pdf` files are always returned, whatever
+/// their content; with `report_mislabeled`, files whose extension and
+/// content disagree are flagged, so a `.pdf` that is not a PDF is marked
+/// [`Mislabel::NotPdf`] rather than reported as corrupt.
+///
+/// With `follow_symlinks`, links are resolved: directory cycles are not
+/// re-entered, a file reached through several links is returned once, and
+/// links whose target is missing are returned with `dangling_target` set.
+pub fn scan(root: &Path, options: &ScanOptions) -> Result<Vec<DiscoveredFile>> {
+    let selector = Selector::new(root, options)?;
+    let mut discovered = Vec::new();
+
+    for entry in walk_builder(root, options)?.build() {
+        let found = match entry {
+            Ok(entry) => selector.select(entry)?,
+            Err(e) => selector.recover(e)?,
+        };
+        if let Some(found) = found {
+            discovered.push(found);
+        }
+    }
+
+    Ok(discovered)
+}
+
+/// Files found by [`scan_parallel`], in the order the walker finds them
+///
+/// Iterating blocks until the next file is found; the iterator ends once
+/// the whole tree has been walked. Dropping it stops the walk.
+pub struct ScanStream {
+    receiver: Receiver<std::result::Result<DiscoveredFile, ignore::Error>>,
+}
+
+impl Iterator for ScanStream {
+    type Item = Result<DiscoveredFile>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.receiver.recv().ok().map(|item| item.map_err(Into::into))
+    }
+}
+
+/// Walk `root` on background threads, streaming candidates as they are found
+///
+/// Selects the same files as [`scan`], but directories are walked in
+/// parallel and results can be consumed while the walk is still running.
+/// Errors for individual entries are yielded without ending the walk.
+pub fn scan_parallel(root: &Path, options: &ScanOptions) -> Result<ScanStream> {
+    let selector = Arc::new(Selector::new(root, options)?);
+    let walker = walk_builder(root, options)?.build_parallel();
+    let (sender, receiver) = mpsc::sync_channel(STREAM_BUFFER);
+
+    thread::Builder::new()
+        .name("pdf-scan".to_string())
+        .spawn(move || {
+            walker.run(|| {
+                let sender = sender.clone();
+                let selector = Arc::clone(&selector);
+                Box::new(move |entry| {
+                    let selected = match entry {
+                        Ok(entry) => selector.select(entry),
+                        Err(e) => selector.recover(e),
+                    };
+                    let item = match selected {
+                        Ok(None) => return WalkState::Continue,
+                        Ok(Some(found)) => Ok(found),
+                        Err(e) => Err(e),
+                    };
+                    // The consumer hung up; stop walking
+                    if sender.send(item).is_err() {
+                        return WalkState::Quit;
                     }
-                }
-            }
+                    WalkState::Continue
+                })
+            });
+        })?;
+
+    Ok(ScanStream { receiver })
+}
+
+/// Per-entry filtering shared by [`scan`] and [`scan_parallel`]
+struct Selector {
+    root: PathBuf,
+    include: Option<GlobSet>,
+    options: ScanOptions,
+    /// Device and inode of every file returned so far, when following links
+    seen: Option<Mutex<HashSet<(u64, u64)>>>,
+}
+
+impl Selector {
+    fn new(root: &Path, options: &ScanOptions) -> Result<Self> {
+        Ok(Self {
+            root: root.to_path_buf(),
+            include: include_set(&options.include)?,
+            options: options.clone(),
+            seen: options.follow_symlinks.then(Mutex::default),
+        })
+    }
+
+    fn select(&self, entry: DirEntry) -> std::result::Result<Option<DiscoveredFile>, ignore::Error> {
+        if !entry.file_type().is_some_and(|file_type| file_type.is_file()) {
+            return Ok(None);
+        }
+        if !self.included(entry.path()) {
+            return Ok(None);
+        }
+        let metadata = entry.metadata()?;
+        if !self.options.accepts(metadata.len(), metadata.modified().ok()) {
+            return Ok(None);
         }
+        let found = classify(entry.into_path(), &self.options);
+        if found.is_some() && !self.first_sighting(&metadata) {
+            return Ok(None);
+        }
+        Ok(found.map(|found| DiscoveredFile {
+            root: Some(self.root.clone()),
+            ..found
+        }))
     }
 
-    Ok(pdf_files)
+    /// Turn a walk error for a dangling symlink into a discovered file
+    ///
+    /// Symlink loops are dropped, since the walker has already refused to
+    /// descend into the cycle. Other errors are passed back unchanged. A
+    /// dangling link is reported when its name or its target's name would
+    /// make it a candidate.
+    fn recover(&self, error: ignore::Error) -> std::result::Result<Option<DiscoveredFile>, ignore::Error> {
+        if is_loop(&error) {
+            return Ok(None);
+        }
+        let Some(path) = error_path(&error) else {
+            return Err(error);
+        };
+        let Some(target) = dangling_target(path) else {
+            return Err(error);
+        };
+        if !self.included(path) {
+            return Ok(None);
+        }
+        let candidate = self.options.discovery == Discovery::Content
+            || has_pdf_extension(path)
+            || has_pdf_extension(&target);
+        if !candidate {
+            return Ok(None);
+        }
+        Ok(Some(DiscoveredFile {
+            dangling_target: Some(target),
+            root: Some(self.root.clone()),
+            ..DiscoveredFile::new(path.to_path_buf())
+        }))
+    }
+
+    fn included(&self, path: &Path) -> bool {
+        match self.include {
+            Some(ref include) => include.is_match(path.strip_prefix(&self.root).unwrap_or(path)),
+            None => true,
+        }
+    }
+
+    /// Record the file's identity; false if it was already returned via another link
+    fn first_sighting(&self, metadata: &Metadata) -> bool {
+        match (&self.seen, file_id(metadata)) {
+            (Some(seen), Some(id)) => seen.lock().map(|mut seen| seen.insert(id)).unwrap_or(true),
+            _ => true,
+        }
+    }
+}
+
+#[cfg(unix)]
+fn file_id(metadata: &Metadata) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+    Some((metadata.dev(), metadata.ino()))
+}
+
+#[cfg(not(unix))]
+fn file_id(_metadata: &Metadata) -> Option<(u64, u64)> {
+    None
+}
+
+/// The path an ignore error refers to, if any
+fn error_path(error: &ignore::Error) -> Option<&Path> {
+    match error {
+        ignore::Error::WithPath { path, .. } => Some(path),
+        ignore::Error::WithDepth { err, .. } | ignore::Error::WithLineNumber { err, .. } => error_path(err),
+        _ => None,
+    }
+}
+
+/// Whether an ignore error reports a symlink loop, however it is wrapped
+fn is_loop(error: &ignore::Error) -> bool {
+    match error {
+        ignore::Error::Loop { .. } => true,
+        ignore::Error::WithPath { err, .. }
+        | ignore::Error::WithDepth { err, .. }
+        | ignore::Error::WithLineNumber { err, .. } => is_loop(err),
+        _ => false,
+    }
+}
+
+/// Link target of `path` if it is a symlink whose target does not exist
+fn dangling_target(path: &Path) -> Option<PathBuf> {
+    let link = fs::symlink_metadata(path).ok()?;
+    if !link.file_type().is_symlink() {
+        return None;
+    }
+    match fs::metadata(path) {
+        Err(e) if e.kind() == io::ErrorKind::NotFound => fs::read_link(path).ok(),
+        _ => None,
+    }
+}
+
+/// Directory walker honoring the depth limit, exclude globs and ignore files
+fn walk_builder(root: &Path, options: &ScanOptions) -> Result<WalkBuilder> {
+    let mut builder = WalkBuilder::new(root);
+    builder
+        .standard_filters(false)
+        .follow_links(options.follow_symlinks)
+        .max_depth(options.effective_max_depth());
+
+    if options.use_ignore_files {
+        builder.add_custom_ignore_filename(IGNORE_FILE_NAME);
+    }
+
+    if !options.exclude.is_empty() {
+        let mut overrides = OverrideBuilder::new(root);
+        for pattern in &options.exclude {
+            overrides.add(&format!("!{}", pattern))?;
+        }
+        builder.overrides(overrides.build()?);
+    }
+
+    Ok(builder)
+}
+
+/// Matcher for include globs, or `None` to include everything
+///
+/// As in gitignore, a pattern without a `/` matches the file name at any
+/// depth; other patterns match the path relative to the scan root.
+fn include_set(patterns: &[String]) -> Result<Option<GlobSet>> {
+    if patterns.is_empty() {
+        return Ok(None);
+    }
+    let mut builder = GlobSetBuilder::new();
+    for original in patterns {
+        let pattern = original.trim_start_matches('/');
+        let pattern = if pattern.contains('/') {
+            pattern.to_string()
+        } else {
+            format!("**/{}", pattern)
+        };
+        let glob = GlobBuilder::new(&pattern)
+            .literal_separator(true)
+            .build()
+            .map_err(|e| glob_error(Some(original), e))?;
+        builder.add(glob);
+    }
+    Ok(Some(builder.build().map_err(|e| glob_error(None, e))?))
+}
+
+fn glob_error(glob: Option<&String>, error: globset::Error) -> ignore::Error {
+    ignore::Error::Glob {
+        glob: glob.cloned(),
+        err: error.to_string(),
+    }
+}
+
+/// Decide whether a file is a candidate, sniffing its content if needed
+fn classify(path: PathBuf, options: &ScanOptions) -> Option<DiscoveredFile> {
+    if let Some(kind) = ArchiveKind::from_path(&path).filter(|_| options.scan_archives) {
+        return Some(DiscoveredFile {
+            archive: Some(kind),
+            ..DiscoveredFile::new(path)
+        });
+    }
+
+    let named_pdf = has_pdf_extension(&path);
+    let sniff = options.discovery == Discovery::Content || (options.report_mislabeled && named_pdf);
+    if !named_pdf && !sniff {
+        return None;
+    }
+
+    // Unreadable files are left to the validator to report
+    let is_pdf = if sniff { sniff_pdf(&path).unwrap_or(named_pdf) } else { true };
+
+    // A `.pdf` file without a header stays a candidate either way: flagged
+    // when mislabels are reported, otherwise left for the validator to fail
+    let mislabel = match (named_pdf, is_pdf) {
+        (true, true) => None,
+        (false, true) => Some(Mislabel::PdfWithoutExtension),
+        (true, false) => Some(Mislabel::NotPdf),
+        (false, false) => return None,
+    };
+
+    Some(DiscoveredFile {
+        mislabel: mislabel.filter(|_| options.report_mislabeled),
+        ..DiscoveredFile::new(path)
+    })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::fs::File;
+    use std::fs::{self, File};
     use tempfile::TempDir;
 
     #[test]
@@ -81,4 +471,161 @@ mod tests {
         let files = collect_pdf_files(temp_dir.path(), true).unwrap();
         assert_eq!(files.len(), 2);
     }
+
+    #[test]
+    fn test_scan_by_content() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        fs::write(dir.join("real.pdf"), b"%PDF-1.4\n%%EOF").unwrap();
+        fs::write(dir.join("scan.PDF.tmp"), b"junk\r\n%PDF-1.4\n%%EOF").unwrap();
+        fs::write(dir.join("error.pdf"), b"<html>404</html>").unwrap();
+        fs::write(dir.join("notes.txt"), b"hello").unwrap();
+
+        let options = ScanOptions {
+            discovery: Discovery::Content,
+            report_mislabeled: true,
+            ..ScanOptions::default()
+        };
+        let mut found = scan(dir, &options).unwrap();
+        found.sort_by(|a, b| a.path.cmp(&b.path));
+        let found: Vec<_> = found
+            .iter()
+            .map(|f| (f.path.file_name().unwrap().to_str().unwrap(), f.mislabel))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("error.pdf", Some(Mislabel::NotPdf)),
+                ("real.pdf", None),
+                ("scan.PDF.tmp", Some(Mislabel::PdfWithoutExtension)),
+            ]
+        );
+
+        let options = ScanOptions {
+            discovery: Discovery::Content,
+            ..ScanOptions::default()
+        };
+        // error.pdf is still validated, and fails, without --report-mislabeled
+        let found = scan(dir, &options).unwrap();
+        assert_eq!(found.len(), 3);
+        assert!(found.iter().all(|f| f.mislabel.is_none()));
+
+        let found = scan(dir, &ScanOptions::default()).unwrap();
+        assert_eq!(found.len(), 2);
+    }
+
+    #[test]
+    fn test_scan_parallel_matches_scan() {
+        let temp_dir = TempDir::new().unwrap();
+        for i in 0..20 {
+            let sub = temp_dir.path().join(format!("dir{}", i % 4));
+            fs::create_dir_all(&sub).unwrap();
+            File::create(sub.join(format!("file{}.pdf", i))).unwrap();
+            File::create(sub.join(format!("file{}.txt", i))).unwrap();
+        }
+        let options = ScanOptions {
+            recursive: true,
+            ..ScanOptions::default()
+        };
+
+        let mut sequential: Vec<_> = scan(temp_dir.path(), &options).unwrap();
+        let mut streamed: Vec<_> = scan_parallel(temp_dir.path(), &options)
+            .unwrap()
+            .collect::<Result<_>>()
+            .unwrap();
+        sequential.sort_by(|a, b| a.path.cmp(&b.path));
+        streamed.sort_by(|a, b| a.path.cmp(&b.path));
+        assert_eq!(streamed.len(), 20);
+        assert_eq!(streamed, sequential);
+    }
+
+    #[test]
+    fn test_scan_filters() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        for sub in ["keep/deeper", "drafts", "ignored"] {
+            fs::create_dir_all(dir.join(sub)).unwrap();
+        }
+        fs::write(dir.join("a.pdf"), vec![b'x'; 10]).unwrap();
+        fs::write(dir.join("big.pdf"), vec![b'x'; 5000]).unwrap();
+        fs::write(dir.join("keep/b.pdf"), vec![b'x'; 10]).unwrap();
+        fs::write(dir.join("keep/deeper/c.pdf"), vec![b'x'; 10]).unwrap();
+        fs::write(dir.join("drafts/d.pdf"), vec![b'x'; 10]).unwrap();
+        fs::write(dir.join("ignored/e.pdf"), vec![b'x'; 10]).unwrap();
+        fs::write(dir.join("keep/skip-me.pdf"), vec![b'x'; 10]).unwrap();
+        fs::write(dir.join(IGNORE_FILE_NAME), "ignored/\n").unwrap();
+        fs::write(dir.join("keep").join(IGNORE_FILE_NAME), "skip-*.pdf\n").unwrap();
+
+        let names = |options: &ScanOptions| {
+            let mut names: Vec<String> = scan(dir, options)
+                .unwrap()
+                .into_iter()
+                .map(|f| f.path.strip_prefix(dir).unwrap().to_string_lossy().replace('\\', "/"))
+                .collect();
+            names.sort();
+            names
+        };
+
+        let options = ScanOptions {
+            recursive: true,
+            exclude: vec!["drafts/".to_string()],
+            max_size: Some(1000),
+            ..ScanOptions::default()
+        };
+        assert_eq!(names(&options), vec!["a.pdf", "keep/b.pdf", "keep/deeper/c.pdf"]);
+
+        let options = ScanOptions {
+            max_depth: Some(2),
+            include: vec!["keep/**".to_string()],
+            ..ScanOptions::default()
+        };
+        assert_eq!(names(&options), vec!["keep/b.pdf"]);
+
+        let options = ScanOptions {
+            recursive: true,
+            use_ignore_files: false,
+            min_size: Some(1000),
+            ..ScanOptions::default()
+        };
+        assert_eq!(names(&options), vec!["big.pdf"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_scan_follow_symlinks() {
+        use std::os::unix::fs::symlink;
+
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        fs::create_dir_all(dir.join("store")).unwrap();
+        fs::create_dir_all(dir.join("docs")).unwrap();
+        fs::write(dir.join("store/abc123"), b"%PDF-1.4").unwrap();
+        symlink(dir.join("store/abc123"), dir.join("docs/a.pdf")).unwrap();
+        symlink(dir.join("store/abc123"), dir.join("docs/b.pdf")).unwrap();
+        symlink(dir.join("store/missing"), dir.join("docs/gone.pdf")).unwrap();
+        symlink(dir, dir.join("docs/loop")).unwrap();
+
+        let options = ScanOptions {
+            recursive: true,
+            ..ScanOptions::default()
+        };
+        assert!(scan(dir, &options).unwrap().is_empty());
+
+        let options = ScanOptions {
+            recursive: true,
+            follow_symlinks: true,
+            ..ScanOptions::default()
+        };
+        for found in [
+            scan(dir, &options).unwrap(),
+            scan_parallel(dir, &options).unwrap().collect::<Result<Vec<_>>>().unwrap(),
+        ] {
+            let (dangling, files): (Vec<_>, Vec<_>) = found.into_iter().partition(|f| f.dangling_target.is_some());
+            // a.pdf and b.pdf share an inode, so only one of them is returned
+            assert_eq!(files.len(), 1);
+            assert_eq!(dangling.len(), 1);
+            assert_eq!(dangling[0].path, dir.join("docs/gone.pdf"));
+            assert_eq!(dangling[0].dangling_target, Some(dir.join("store/missing")));
+        }
+    }
 }
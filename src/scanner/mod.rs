@@ -1,7 +1,15 @@
 //! File scanning and collection functionality
 
 pub mod file_scanner;
+pub mod content_sniffer;
+pub mod scan_options;
+pub mod file_list;
+pub mod archive;
 pub mod duplicate_detector;
 
-pub use file_scanner::{collect_pdf_files, ValidationResult};
-pub use duplicate_detector::{compute_file_hash, find_duplicates, DuplicateInfo};
+pub use file_scanner::{collect_pdf_files, scan, scan_parallel, DiscoveredFile, ScanStream, ValidationResult};
+pub use scan_options::{parse_size, parse_time, Discovery, ScanOptions};
+pub use content_sniffer::{find_pdf_header, sniff_pdf, Mislabel};
+pub use file_list::{parse_file_list, read_file_list};
+pub use archive::{for_each_pdf_member, member_path, validate_archive, validate_archive_with, ArchiveKind, ArchiveLimits, ArchiveMember};
+pub use duplicate_detector::{compute_data_hash, compute_file_hash, find_duplicates, find_result_duplicates, DuplicateInfo};
@@ -0,0 +1,208 @@
+//! Options controlling which files a scan picks up
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Name of the per-directory ignore file (gitignore syntax)
+pub const IGNORE_FILE_NAME: &str = ".pdfvalidatorignore";
+
+/// How candidate files are recognised during a scan
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Discovery {
+    /// Files named `*.pdf` (any case)
+    #[default]
+    Extension,
+    /// Files whose first 1024 bytes contain `%PDF-`, whatever their name
+    Content,
+}
+
+/// Which files a directory scan returns
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScanOptions {
+    /// Descend into subdirectories
+    pub recursive: bool,
+    /// Deepest level to visit; files directly in the root are at depth 1.
+    /// Overrides `recursive` when set.
+    pub max_depth: Option<usize>,
+    /// Only files matching one of these globs are returned (all if empty)
+    pub include: Vec<String>,
+    /// Files and directories matching these globs are skipped
+    pub exclude: Vec<String>,
+    /// Honor `.pdfvalidatorignore` files anywhere in the tree
+    pub use_ignore_files: bool,
+    /// Skip files smaller than this (bytes)
+    pub min_size: Option<u64>,
+    /// Skip files larger than this (bytes)
+    pub max_size: Option<u64>,
+    /// Skip files last modified before this time
+    pub newer_than: Option<SystemTime>,
+    /// Skip files last modified after this time
+    pub older_than: Option<SystemTime>,
+    pub discovery: Discovery,
+    /// Flag files whose extension and content disagree
+    pub report_mislabeled: bool,
+    /// Resolve symlinks to files and directories instead of skipping them
+    pub follow_symlinks: bool,
+    /// Return ZIP and TAR archives so their PDF members can be validated
+    pub scan_archives: bool,
+}
+
+impl Default for ScanOptions {
+    fn default() -> Self {
+        Self {
+            recursive: false,
+            max_depth: None,
+            include: Vec::new(),
+            exclude: Vec::new(),
+            use_ignore_files: true,
+            min_size: None,
+            max_size: None,
+            newer_than: None,
+            older_than: None,
+            discovery: Discovery::Extension,
+            report_mislabeled: false,
+            follow_symlinks: false,
+            scan_archives: false,
+        }
+    }
+}
+
+impl ScanOptions {
+    /// Depth limit to hand to the walker, if any
+    pub fn effective_max_depth(&self) -> Option<usize> {
+        match (self.max_depth, self.recursive) {
+            (Some(depth), _) => Some(depth),
+            (None, true) => None,
+            (None, false) => Some(1),
+        }
+    }
+
+    /// Whether a file of this size and modification time passes the filters
+    pub fn accepts(&self, size: u64, modified: Option<SystemTime>) -> bool {
+        if self.min_size.is_some_and(|min| size < min) || self.max_size.is_some_and(|max| size > max) {
+            return false;
+        }
+        if self.newer_than.is_none() && self.older_than.is_none() {
+            return true;
+        }
+        // Files without a usable mtime cannot satisfy a time filter
+        let Some(modified) = modified else {
+            return false;
+        };
+        !(self.newer_than.is_some_and(|t| modified < t) || self.older_than.is_some_and(|t| modified > t))
+    }
+}
+
+/// Parse a size such as `512`, `64K`, `10M` or `2G` (binary units)
+pub fn parse_size(input: &str) -> Result<u64, String> {
+    let input = input.trim();
+    let split = input.find(|c: char| !c.is_ascii_digit()).unwrap_or(input.len());
+    let (digits, unit) = input.split_at(split);
+    let value: u64 = digits
+        .parse()
+        .map_err(|_| format!("invalid size '{}': expected a number with optional K, M or G suffix", input))?;
+    let multiplier: u64 = match unit.trim().to_ascii_uppercase().as_str() {
+        "" | "B" => 1,
+        "K" | "KB" | "KIB" => 1 << 10,
+        "M" | "MB" | "MIB" => 1 << 20,
+        "G" | "GB" | "GIB" => 1 << 30,
+        _ => return Err(format!("invalid size unit in '{}': use K, M or G", input)),
+    };
+    value
+        .checked_mul(multiplier)
+        .ok_or_else(|| format!("size '{}' is too large", input))
+}
+
+/// Parse a point in time: an age such as `30m`, `12h`, `7d` or `2w`
+/// (relative to now), or a UTC date `YYYY-MM-DD`
+pub fn parse_time(input: &str) -> Result<SystemTime, String> {
+    parse_time_at(input, SystemTime::now())
+}
+
+fn parse_time_at(input: &str, now: SystemTime) -> Result<SystemTime, String> {
+    let input = input.trim();
+    if let Some(date) = parse_date(input) {
+        return Ok(date);
+    }
+
+    let split = input.find(|c: char| !c.is_ascii_digit()).unwrap_or(input.len());
+    let (digits, unit) = input.split_at(split);
+    let value: u64 = digits
+        .parse()
+        .map_err(|_| format!("invalid time '{}': expected an age like 7d or a date like 2024-01-31", input))?;
+    let seconds = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        "w" => 7 * 24 * 60 * 60,
+        _ => return Err(format!("invalid time unit in '{}': use s, m, h, d or w", input)),
+    };
+    value
+        .checked_mul(seconds)
+        .and_then(|age| now.checked_sub(Duration::from_secs(age)))
+        .ok_or_else(|| format!("time '{}' is out of range", input))
+}
+
+/// Midnight UTC of a `YYYY-MM-DD` date
+fn parse_date(input: &str) -> Option<SystemTime> {
+    let mut parts = input.splitn(3, '-');
+    let year: i64 = parts.next()?.parse().ok()?;
+    let month: i64 = parts.next()?.parse().ok()?;
+    let day: i64 = parts.next()?.parse().ok()?;
+    if input.len() != 10 || !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+
+    // Days since the Unix epoch in the proleptic Gregorian calendar
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let year_of_era = y - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = era * 146_097 + day_of_era - 719_468;
+
+    let seconds = u64::try_from(days).ok()?.checked_mul(24 * 60 * 60)?;
+    UNIX_EPOCH.checked_add(Duration::from_secs(seconds))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_size() {
+        assert_eq!(parse_size("512"), Ok(512));
+        assert_eq!(parse_size("64K"), Ok(64 * 1024));
+        assert_eq!(parse_size("10mb"), Ok(10 * 1024 * 1024));
+        assert!(parse_size("10X").is_err());
+        assert!(parse_size("M").is_err());
+    }
+
+    #[test]
+    fn test_parse_time() {
+        let now = UNIX_EPOCH + Duration::from_secs(1_000_000);
+        assert_eq!(parse_time_at("2h", now), Ok(now - Duration::from_secs(7200)));
+        assert_eq!(parse_time_at("1970-01-02", now), Ok(UNIX_EPOCH + Duration::from_secs(86_400)));
+        assert_eq!(
+            parse_time_at("2024-03-01", now),
+            Ok(UNIX_EPOCH + Duration::from_secs(1_709_251_200))
+        );
+        assert!(parse_time_at("7 days", now).is_err());
+    }
+
+    #[test]
+    fn test_accepts_size_and_time() {
+        let now = SystemTime::now();
+        let options = ScanOptions {
+            min_size: Some(10),
+            max_size: Some(100),
+            newer_than: Some(now - Duration::from_secs(60)),
+            ..ScanOptions::default()
+        };
+        assert!(options.accepts(50, Some(now)));
+        assert!(!options.accepts(5, Some(now)));
+        assert!(!options.accepts(500, Some(now)));
+        assert!(!options.accepts(50, Some(now - Duration::from_secs(3600))));
+        assert!(!options.accepts(50, None));
+    }
+}
@@ -408,3 +408,34 @@ fn test_circuit_breaker_parallel_validation() {
     // Test passes if no panic occurred
     assert!(results.len() == files.len());
 }
+
+/// Test that differently configured validators have independent breakers
+#[test]
+fn test_validators_have_independent_breakers() {
+    let temp_dir = TempDir::new().unwrap();
+    let toxic_file = temp_dir.path().join("toxic.pdf");
+    let mut file = File::create(&toxic_file).unwrap();
+    let mut content = Vec::from(b"%PDF-1.7\nTOXIC CONTENT\n".as_slice());
+    content.extend_from_slice(&[b' '; 200]);
+    content.extend_from_slice(b"\n%%EOF");
+    file.write_all(&content).unwrap();
+    file.flush().unwrap();
+
+    let fragile = Validator::builder()
+        .circuit_breaker(2, Duration::from_secs(60))
+        .build();
+    let sturdy = Validator::builder()
+        .circuit_breaker(1000, Duration::from_secs(60))
+        .build();
+
+    for _ in 0..3 {
+        let _ = fragile.validate(&toxic_file);
+    }
+    assert!(fragile.circuit_breaker().is_open());
+
+    let outcome = fragile.validate(&toxic_file);
+    assert_eq!(outcome.category, Some(FailureCategory::CircuitOpen));
+
+    let _ = sturdy.validate(&toxic_file);
+    assert!(!sturdy.circuit_breaker().is_open(), "Breakers must not be shared");
+}
@@ -0,0 +1,71 @@
+//! Shared helpers for integration tests
+//!
+//! Builds small but structurally correct PDFs (with a real xref table) so
+//! tests can exercise the pdf-rs code paths, not just the quick checks.
+
+#![allow(dead_code)]
+
+/// Assemble numbered objects (starting at `1 0 obj`) into a PDF with a
+/// correct cross-reference table. Object 1 must be the catalog.
+pub fn assemble_pdf(objects: &[Vec<u8>]) -> Vec<u8> {
+    let mut out = b"%PDF-1.4\n%\xE2\xE3\xCF\xD3\n".to_vec();
+    let mut offsets = Vec::with_capacity(objects.len());
+
+    for (index, body) in objects.iter().enumerate() {
+        offsets.push(out.len());
+        out.extend_from_slice(format!("{} 0 obj\n", index + 1).as_bytes());
+        out.extend_from_slice(body);
+        out.extend_from_slice(b"\nendobj\n");
+    }
+
+    let xref_offset = out.len();
+    out.extend_from_slice(format!("xref\n0 {}\n", objects.len() + 1).as_bytes());
+    out.extend_from_slice(b"0000000000 65535 f \n");
+    for offset in offsets {
+        out.extend_from_slice(format!("{:010} 00000 n \n", offset).as_bytes());
+    }
+    out.extend_from_slice(
+        format!(
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+            objects.len() + 1,
+            xref_offset
+        )
+        .as_bytes(),
+    );
+    out
+}
+
+/// Build a stream object body with a correct `/Length`
+pub fn stream_object(extra_dict: &str, data: &[u8]) -> Vec<u8> {
+    let mut body = format!("<< /Length {} {} >>\nstream\n", data.len(), extra_dict).into_bytes();
+    body.extend_from_slice(data);
+    body.extend_from_slice(b"\nendstream");
+    body
+}
+
+/// Build a PDF with `pages` pages
+///
+/// Pages whose index appears in `broken_pages` point their `/Contents` at an
+/// object that does not exist.
+pub fn build_pdf(pages: usize, broken_pages: &[usize]) -> Vec<u8> {
+    let kids: Vec<String> = (0..pages).map(|i| format!("{} 0 R", 3 + 2 * i)).collect();
+
+    let mut objects = vec![
+        b"<< /Type /Catalog /Pages 2 0 R >>".to_vec(),
+        format!("<< /Type /Pages /Kids [{}] /Count {} >>", kids.join(" "), pages).into_bytes(),
+    ];
+
+    for i in 0..pages {
+        let contents = if broken_pages.contains(&i) { 999 } else { 4 + 2 * i };
+        objects.push(
+            format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 612 792] /Resources << >> /Contents {} 0 R >>",
+                contents
+            )
+            .into_bytes(),
+        );
+        objects.push(stream_object("", b"q Q"));
+    }
+
+    assemble_pdf(&objects)
+}
@@ -0,0 +1,73 @@
+//! Integration tests for deep (full page-tree) validation
+
+mod common;
+
+use pdf_validator_rs::prelude::*;
+use std::io::Write;
+use tempfile::NamedTempFile;
+
+fn write_pdf(content: &[u8]) -> NamedTempFile {
+    let mut temp_file = NamedTempFile::new().unwrap();
+    temp_file.write_all(content).unwrap();
+    temp_file.flush().unwrap();
+    temp_file
+}
+
+/// Test that a well-formed multi-page PDF passes both levels
+#[test]
+fn test_well_formed_pdf_passes_deep_validation() {
+    let temp_file = write_pdf(&common::build_pdf(5, &[]));
+
+    let standard = Validator::default().validate(temp_file.path());
+    assert_eq!(standard.verdict, Verdict::Valid, "{}", standard);
+
+    let deep = Validator::builder().level(ValidationLevel::Deep).build();
+    let outcome = deep.validate(temp_file.path());
+    assert_eq!(outcome.verdict, Verdict::Valid, "{}", outcome);
+    assert!(outcome.failed_pages.is_empty());
+}
+
+/// Test that a broken late page is only caught by deep validation
+#[test]
+fn test_deep_validation_reports_broken_pages() {
+    let temp_file = write_pdf(&common::build_pdf(6, &[3, 5]));
+
+    let standard = Validator::default().validate(temp_file.path());
+    assert!(standard.is_valid(), "Standard level only loads the first page");
+
+    let deep = Validator::builder().level(ValidationLevel::Deep).build();
+    let outcome = deep.validate(temp_file.path());
+
+    assert_eq!(outcome.verdict, Verdict::Invalid, "{}", outcome);
+    assert_eq!(outcome.category, Some(FailureCategory::PageLoadFailure));
+    assert_eq!(outcome.stage, Some(ValidationStage::PageTree));
+
+    let pages: Vec<u32> = outcome.failed_pages.iter().map(|p| p.page).collect();
+    assert_eq!(pages, vec![3, 5]);
+}
+
+/// Test that stream verification reports corrupt Flate data by object number
+#[test]
+fn test_stream_verification_reports_corrupt_streams() {
+    let objects = vec![
+        b"<< /Type /Catalog /Pages 2 0 R >>".to_vec(),
+        b"<< /Type /Pages /Kids [3 0 R] /Count 1 >>".to_vec(),
+        b"<< /Type /Page /Parent 2 0 R /MediaBox [0 0 612 792] /Resources << >> /Contents 4 0 R >>".to_vec(),
+        common::stream_object("", b"q Q"),
+        common::stream_object("/Filter /FlateDecode", b"this is not zlib data"),
+    ];
+    let temp_file = write_pdf(&common::assemble_pdf(&objects));
+
+    let outcome = Validator::default().validate(temp_file.path());
+    assert_eq!(outcome.verdict, Verdict::Valid, "{}", outcome);
+
+    let verifying = Validator::builder().verify_streams(true).build();
+    let outcome = verifying.validate(temp_file.path());
+
+    assert_eq!(outcome.verdict, Verdict::Invalid, "{}", outcome);
+    assert_eq!(outcome.category, Some(FailureCategory::StreamError));
+    assert_eq!(outcome.stage, Some(ValidationStage::Streams));
+    assert_eq!(outcome.failed_streams.len(), 1);
+    assert_eq!(outcome.failed_streams[0].object, 5);
+    assert_eq!(outcome.failed_streams[0].generation, 0);
+}
@@ -130,10 +130,7 @@ fn test_parallel_mixed_validity() {
     // Process in parallel - should not panic
     let results: Vec<ValidationResult> = files
         .par_iter()
-        .map(|path| ValidationResult {
-            path: path.clone(),
-            is_valid: validate_pdf(path, false),
-        })
+        .map(|path| ValidationResult::new(path.clone(), validate_pdf_outcome(path)))
         .collect();
 
     assert_eq!(results.len(), 20, "All files should be processed");
@@ -146,6 +143,10 @@ fn test_nonexistent_file() {
     let fake_path = PathBuf::from("/tmp/this_file_does_not_exist_xyz123.pdf");
     let result = validate_pdf(&fake_path, false);
     assert!(!result, "Non-existent file should be invalid");
+
+    let outcome = validate_pdf_outcome(&fake_path);
+    assert_eq!(outcome.verdict, Verdict::Errored);
+    assert_eq!(outcome.category, Some(FailureCategory::IoError));
 }
 
 /// Test lenient mode with malformed PDFs
@@ -162,6 +163,7 @@ fn test_lenient_mode_with_malformed_pdfs() {
 
 /// Test that pdf-rs specific validation handles errors
 #[test]
+#[allow(clippy::single_match)]
 fn test_pdf_rs_validation_with_bad_data() {
     let mut temp_file = NamedTempFile::new().unwrap();
     temp_file.write_all(b"%PDF-1.7\n1 0 obj\n<<< INVALID >>>\nendobj\n%%EOF").unwrap();
@@ -258,3 +260,129 @@ fn test_detailed_validation_error_reporting() {
         }
     }
 }
+
+/// Test that a file which blocks forever is reported as timed out
+#[cfg(unix)]
+#[test]
+fn test_per_file_timeout() {
+    use std::process::Command;
+    use std::time::{Duration, Instant};
+
+    let temp_dir = TempDir::new().unwrap();
+    // Opening a FIFO with no writer blocks indefinitely
+    let fifo = temp_dir.path().join("stuck.pdf");
+    let status = Command::new("mkfifo").arg(&fifo).status().unwrap();
+    assert!(status.success());
+
+    let validator = Validator::builder()
+        .timeout(Some(Duration::from_millis(200)))
+        .build();
+
+    let start = Instant::now();
+    let outcome = validator.validate(&fifo);
+
+    assert_eq!(outcome.verdict, Verdict::TimedOut);
+    assert_eq!(outcome.category, Some(FailureCategory::Timeout));
+    assert!(start.elapsed() < Duration::from_secs(5));
+}
+
+/// Test that files stuck past their timeout are capped, and files beyond
+/// the cap are reported as timed out without being validated
+#[test]
+fn test_abandoned_timeout_threads_are_capped() {
+    use pdf_validator_rs::core::validator::MAX_ABANDONED_THREADS;
+    use std::sync::{Arc, RwLock};
+    use std::time::{Duration, Instant};
+
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("slow.pdf");
+    let mut file = File::create(&file_path).unwrap();
+    file.write_all(b"%PDF-1.7\nBAD CONTENT").unwrap();
+    file.write_all(&[b' '; 128]).unwrap();
+    file.write_all(b"\n%%EOF\n").unwrap();
+
+    // Parsing blocks until the gate opens
+    let gate = Arc::new(RwLock::new(()));
+    let closed = gate.write().unwrap();
+    let hook_gate = gate.clone();
+    let validator = Validator::builder()
+        .timeout(Some(Duration::from_millis(20)))
+        .build()
+        .with_parse_hook(Arc::new(move |_| drop(hook_gate.read())));
+
+    for _ in 0..MAX_ABANDONED_THREADS {
+        assert_eq!(validator.validate(&file_path).verdict, Verdict::TimedOut);
+    }
+    let skipped = validator.validate(&file_path);
+    assert_eq!(skipped.verdict, Verdict::TimedOut);
+    assert!(skipped.message.as_deref().unwrap().starts_with("Not validated"), "{}", skipped);
+
+    // Once the stuck threads finish, files are validated again
+    drop(closed);
+    let start = Instant::now();
+    loop {
+        let outcome = validator.validate(&file_path);
+        if outcome.verdict != Verdict::TimedOut {
+            break;
+        }
+        assert!(start.elapsed() < Duration::from_secs(10), "{}", outcome);
+        std::thread::sleep(Duration::from_millis(20));
+    }
+}
+
+/// Test that a file whose validation panics doesn't poison the batch
+#[test]
+fn test_panicking_file_does_not_poison_batch() {
+    use rayon::prelude::*;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    let temp_dir = TempDir::new().unwrap();
+    let mut files = Vec::new();
+    for i in 0..10 {
+        let file_path = temp_dir.path().join(format!("test_{}.pdf", i));
+        let mut file = File::create(&file_path).unwrap();
+        // Padded past the minimum size so the quick check lets it through
+        file.write_all(b"%PDF-1.7\nBAD CONTENT").unwrap();
+        file.write_all(&[b' '; 128]).unwrap();
+        file.write_all(b"\n%%EOF\n").unwrap();
+        files.push(file_path);
+    }
+    let poisoned = files[3].clone();
+
+    // Stand-in for a parser bug triggered by one specific file
+    let trigger = poisoned.clone();
+    let validator = Validator::builder()
+        .circuit_breaker(1000, Duration::from_secs(60))
+        .build()
+        .with_parse_hook(Arc::new(move |path| {
+            if path == trigger {
+                panic!("parser bug on {}", path.display());
+            }
+        }));
+    let results: Vec<ValidationResult> = files
+        .par_iter()
+        .map(|path| ValidationResult::new(path.clone(), validator.validate(path)))
+        .collect();
+
+    assert_eq!(results.len(), 10, "All files should be processed");
+    for result in &results {
+        if result.path == poisoned {
+            assert_eq!(result.outcome.verdict, Verdict::Panicked);
+            assert!(result.outcome.message.as_deref().unwrap().contains("parser bug"));
+        } else {
+            assert_ne!(result.outcome.verdict, Verdict::Panicked);
+        }
+    }
+    // The panic counts towards the circuit breaker like any parse failure
+    assert_eq!(validator.circuit_breaker().failure_count(), 10);
+}
+
+/// Test that caught panics are reported with their message
+#[test]
+fn test_catch_panic_reports_message() {
+    assert_eq!(catch_panic(|| 7), Ok(7));
+    assert_eq!(catch_panic(|| -> u8 { panic!("boom") }), Err("boom".to_string()));
+    let message = format!("boom {}", 2);
+    assert_eq!(catch_panic(|| -> u8 { panic!("{}", message) }), Err("boom 2".to_string()));
+}
@@ -0,0 +1,87 @@
+//! Integration tests for validating PDFs from memory and readers
+
+mod common;
+
+use pdf_validator_rs::prelude::*;
+use std::io::{Cursor, Write};
+use std::time::Duration;
+use tempfile::NamedTempFile;
+
+/// Test that bytes, readers and files give the same outcome
+#[test]
+fn test_bytes_reader_and_path_agree() {
+    let cases = vec![
+        common::build_pdf(3, &[]),
+        b"This is not a PDF at all, just garbage data!".to_vec(),
+        b"%PDF-1.7\nRANDOM GARBAGE DATA HERE, padded well past the minimum file size floor...........\n%%EOF"
+            .to_vec(),
+    ];
+
+    let validator = Validator::default();
+    for content in cases {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(&content).unwrap();
+        temp_file.flush().unwrap();
+
+        let from_path = validator.validate(temp_file.path());
+        assert_eq!(validator.validate_bytes(&content), from_path);
+        assert_eq!(validator.validate_reader(Cursor::new(&content)), from_path);
+    }
+}
+
+/// Test that a well-formed document in memory is valid
+#[test]
+fn test_validate_bytes_well_formed() {
+    let content = common::build_pdf(2, &[]);
+    let outcome = validate_bytes(&content);
+    assert_eq!(outcome.verdict, Verdict::Valid, "{}", outcome);
+    assert_eq!(outcome.pages, Some(2));
+    assert_eq!(outcome.pdf_version.as_deref(), Some("1.4"));
+
+    let outcome = validate_reader(Cursor::new(content));
+    assert_eq!(outcome.verdict, Verdict::Valid, "{}", outcome);
+}
+
+/// Test that in-memory validation runs the deep and stream checks
+#[test]
+fn test_validate_bytes_deep_and_timeout() {
+    let content = common::build_pdf(5, &[3]);
+
+    let deep = Validator::builder()
+        .level(ValidationLevel::Deep)
+        .timeout(Some(Duration::from_secs(30)))
+        .build();
+    let outcome = deep.validate_bytes(&content);
+    assert_eq!(outcome.category, Some(FailureCategory::PageLoadFailure), "{}", outcome);
+    assert_eq!(outcome.failed_pages.len(), 1);
+    assert_eq!(outcome.failed_pages[0].page, 3);
+}
+
+/// Test that the reader's quick check rejects non-PDFs and short input
+#[test]
+fn test_validate_reader_quick_check() {
+    let outcome = validate_reader(Cursor::new(b"<html>not found</html>".to_vec()));
+    assert_eq!(outcome.category, Some(FailureCategory::BadHeader));
+
+    let outcome = validate_reader(Cursor::new(b"%PDF".to_vec()));
+    assert_eq!(outcome.category, Some(FailureCategory::TooSmall));
+
+    let validator = Validator::builder().memory_limit(Some(16)).strictness(Strictness::Lenient).build();
+    let outcome = validator.validate_reader(Cursor::new(common::build_pdf(1, &[])));
+    assert_eq!(outcome.verdict, Verdict::ResourceLimitExceeded);
+}
+
+/// Test that leading junk before the header is accepted, as discovery accepts it
+#[test]
+fn test_header_after_leading_junk() {
+    let mut data = b"\xEF\xBB\xBFjunk\r\n".to_vec();
+    data.extend(common::build_pdf(2, &[]));
+
+    let outcome = validate_bytes(&data);
+    assert_eq!(outcome.verdict, Verdict::Valid, "{}", outcome);
+    assert_eq!(outcome.pdf_version.as_deref(), Some("1.4"));
+
+    let mut late = vec![b' '; 1024];
+    late.extend(common::build_pdf(1, &[]));
+    assert_eq!(validate_bytes(&late).category, Some(FailureCategory::BadHeader));
+}
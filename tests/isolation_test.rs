@@ -0,0 +1,131 @@
+//! Integration tests for validating in worker subprocesses
+//!
+//! Misbehaving workers are shell scripts standing in for the real worker;
+//! they read the first bytes the pool sends so they die mid-request.
+#![cfg(unix)]
+
+mod common;
+
+use pdf_validator_rs::core::memory::MEMORY_LIMIT_EXIT_CODE;
+use pdf_validator_rs::prelude::*;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tempfile::TempDir;
+
+/// The worker subcommand of the binary under test
+fn real_worker() -> WorkerCommand {
+    WorkerCommand {
+        program: PathBuf::from(env!("CARGO_BIN_EXE_pdf_validator_rs")),
+        args: vec!["worker".to_string()],
+    }
+}
+
+/// A worker running `body` under `/bin/sh`
+fn script(body: &str) -> WorkerCommand {
+    WorkerCommand {
+        program: PathBuf::from("/bin/sh"),
+        args: vec!["-c".to_string(), body.to_string()],
+    }
+}
+
+fn write_pdf(dir: &Path, name: &str) -> PathBuf {
+    let path = dir.join(name);
+    std::fs::write(&path, common::build_pdf(2, &[])).unwrap();
+    path
+}
+
+/// Test that real workers validate files
+#[test]
+fn test_real_worker_validates_files() {
+    let temp_dir = TempDir::new().unwrap();
+    let pdf = write_pdf(temp_dir.path(), "good.pdf");
+
+    let pool = WorkerPool::new(real_worker(), ValidationOptions::default(), 1);
+    assert_eq!(pool.validate(&pdf).verdict, Verdict::Valid);
+    assert_eq!(pool.validate(&temp_dir.path().join("missing.pdf")).verdict, Verdict::Errored);
+}
+
+/// Test that a worker killed mid-file marks only that file as crashed and
+/// is replaced by a fresh worker for the next one
+#[test]
+fn test_killed_worker_is_replaced() {
+    let temp_dir = TempDir::new().unwrap();
+    let first = write_pdf(temp_dir.path(), "first.pdf");
+    let second = write_pdf(temp_dir.path(), "second.pdf");
+
+    // The first worker dies by SIGKILL; every later one is a real worker
+    let marker = temp_dir.path().join("crashed");
+    let command = script(&format!(
+        "if [ -e '{marker}' ]; then exec '{worker}' worker; fi; touch '{marker}'; head -c 1 >/dev/null; kill -9 $$",
+        marker = marker.display(),
+        worker = env!("CARGO_BIN_EXE_pdf_validator_rs"),
+    ));
+    let pool = WorkerPool::new(command, ValidationOptions::default(), 1);
+
+    let crashed = pool.validate(&first);
+    assert_eq!(crashed.verdict, Verdict::Crashed, "{}", crashed);
+    assert!(crashed.message.as_deref().unwrap().contains("signal 9"), "{}", crashed);
+
+    assert_eq!(pool.validate(&second).verdict, Verdict::Valid);
+}
+
+/// Test that a worker which overruns the timeout is killed
+#[test]
+fn test_stuck_worker_is_killed_on_timeout() {
+    let temp_dir = TempDir::new().unwrap();
+    let pdf = write_pdf(temp_dir.path(), "slow.pdf");
+
+    let options = ValidationOptions {
+        timeout: Some(Duration::from_millis(300)),
+        ..ValidationOptions::default()
+    };
+    let pool = WorkerPool::new(script("exec sleep 30"), options, 1);
+
+    let start = Instant::now();
+    let outcome = pool.validate(&pdf);
+    assert_eq!(outcome.verdict, Verdict::TimedOut, "{}", outcome);
+    let outcome = pool.validate(&pdf);
+    assert_eq!(outcome.verdict, Verdict::TimedOut, "{}", outcome);
+    // Each stuck worker was killed rather than waited for
+    assert!(start.elapsed() < Duration::from_secs(10));
+}
+
+/// Test that a worker exiting with the memory-limit code marks the file as
+/// over its resource limit
+#[test]
+fn test_memory_limit_exit_code_is_reported() {
+    let temp_dir = TempDir::new().unwrap();
+    let pdf = write_pdf(temp_dir.path(), "huge.pdf");
+
+    let options = ValidationOptions {
+        memory_limit: Some(64 * 1024 * 1024),
+        ..ValidationOptions::default()
+    };
+    let command = script(&format!("head -c 1 >/dev/null; exit {}", MEMORY_LIMIT_EXIT_CODE));
+    let pool = WorkerPool::new(command, options, 1);
+
+    let outcome = pool.validate(&pdf);
+    assert_eq!(outcome.verdict, Verdict::ResourceLimitExceeded, "{}", outcome);
+    assert_eq!(outcome.category, Some(FailureCategory::MemoryLimit));
+}
+
+/// Test that a real worker stops at its address-space cap and the file is
+/// reported over its memory limit
+#[test]
+fn test_worker_memory_limit_is_enforced() {
+    let options = ValidationOptions {
+        memory_limit: Some(32 * 1024 * 1024),
+        ..ValidationOptions::default()
+    };
+    let pool = WorkerPool::new(real_worker(), options, 1);
+
+    // The worker cannot even take in a document this large
+    let data = vec![b' '; 256 * 1024 * 1024];
+    let outcome = pool.validate_bytes(Path::new("huge.pdf"), &data);
+    assert_eq!(outcome.verdict, Verdict::ResourceLimitExceeded, "{}", outcome);
+
+    // Its replacement still validates small files
+    let temp_dir = TempDir::new().unwrap();
+    let pdf = write_pdf(temp_dir.path(), "small.pdf");
+    assert_eq!(pool.validate(&pdf).verdict, Verdict::Valid);
+}
@@ -1,6 +1,7 @@
 //! Integration tests for malformed PDF handling
 //!
 //! Tests various types of malformed PDFs to ensure robust error handling
+#![allow(clippy::useless_vec, clippy::same_item_push)]
 
 use pdf_validator_rs::prelude::*;
 use std::io::Write;
@@ -390,3 +391,85 @@ fn test_corrupted_compressed_stream() {
     let _result = validate_pdf(temp_file.path(), false);
     // Should handle decompression errors
 }
+
+/// Test that failures carry a machine-readable category and stage
+#[test]
+fn test_outcome_failure_categories() {
+    let padding = vec![b' '; 200];
+
+    let mut bad_header = Vec::from(b"GARBAGE!".as_slice());
+    bad_header.extend_from_slice(&padding);
+    bad_header.extend_from_slice(b"\n%%EOF");
+
+    let mut missing_eof = Vec::from(b"%PDF-1.7\n".as_slice());
+    missing_eof.extend_from_slice(&padding);
+
+    let cases = vec![
+        (bad_header, FailureCategory::BadHeader),
+        (missing_eof, FailureCategory::MissingEof),
+        (b"%PDF".to_vec(), FailureCategory::TooSmall),
+    ];
+
+    for (content, expected) in cases {
+        let temp_file = create_test_file(&content);
+        let outcome = validate_pdf_outcome(temp_file.path());
+        assert_eq!(outcome.verdict, Verdict::Invalid);
+        assert_eq!(outcome.category, Some(expected));
+        assert_eq!(outcome.stage, Some(ValidationStage::QuickCheck));
+        assert!(outcome.message.is_some());
+    }
+}
+
+/// Test that the detailed API returns typed errors
+#[test]
+fn test_detailed_validation_typed_errors() {
+    let mut content = Vec::from(b"%PDF-1.7\n".as_slice());
+    content.extend_from_slice(&[b' '; 200]);
+
+    let temp_file = create_test_file(&content);
+    match validate_pdf_detailed(temp_file.path()) {
+        Err(ValidationError::MissingEof) => {}
+        other => panic!("Expected MissingEof, got {:?}", other),
+    }
+
+    let missing = std::path::Path::new("/tmp/this_file_does_not_exist_xyz123.pdf");
+    match validate_pdf_detailed(missing) {
+        Err(ValidationError::Io(e)) => assert_eq!(e.kind(), std::io::ErrorKind::NotFound),
+        other => panic!("Expected Io error, got {:?}", other),
+    }
+}
+
+/// Test that quick-check limits come from the validator's options
+#[test]
+fn test_custom_validator_limits() {
+    let temp_file = create_test_file(b"%PDF-1.7\n%%EOF");
+
+    let default_outcome = Validator::default().validate(temp_file.path());
+    assert_eq!(default_outcome.category, Some(FailureCategory::TooSmall));
+
+    let relaxed = Validator::builder().min_file_size(8).build();
+    let outcome = relaxed.validate(temp_file.path());
+    assert_ne!(outcome.category, Some(FailureCategory::TooSmall));
+
+    let tight = Validator::builder().max_file_size(10).min_file_size(1).build();
+    let outcome = tight.validate(temp_file.path());
+    assert_eq!(outcome.category, Some(FailureCategory::TooLarge));
+}
+
+/// Test that files larger than the memory budget are not loaded
+#[test]
+fn test_memory_limit_exceeded() {
+    let mut content = b"%PDF-1.4\n".to_vec();
+    content.extend_from_slice(&[b' '; 256]);
+    content.extend_from_slice(b"\n%%EOF");
+    let temp_file = create_test_file(&content);
+
+    let validator = Validator::builder().memory_limit(Some(64)).build();
+    let outcome = validator.validate(temp_file.path());
+    assert_eq!(outcome.verdict, Verdict::ResourceLimitExceeded);
+    assert_eq!(outcome.category, Some(FailureCategory::MemoryLimit));
+
+    let validator = Validator::builder().memory_limit(Some(1024 * 1024)).build();
+    let outcome = validator.validate(temp_file.path());
+    assert_ne!(outcome.category, Some(FailureCategory::MemoryLimit));
+}